@@ -3,7 +3,10 @@ use crate::game_entities::{
     player::Player, protein_wallet,
 };
 
-use super::action_type::{self, ActionType};
+use super::{
+    action_type::{self, ActionType},
+    actions_finder,
+};
 
 const MASK_TYPE: Action = 0b0000_0011;
 const MASK_ORGAN: Action = 0b0001_1100;
@@ -119,20 +122,24 @@ pub fn is_valid(action: Action, grid: &Grid, player: &Player) -> bool {
         )
 }
 
+/// Same rules as the referee: a ROOT is only placed by a SPORE, which needs a sporer of the
+/// organism facing the target with free cells in between.
 pub fn is_valid_with_root(action: Action, grid: &Grid, player: &Player) -> bool {
     let action_type = get_type(action);
     let coord_target = get_coord_target(action);
     let coord_root = get_coord_source(action);
     let direction = get_direction(action);
-    if ActionType::Wait == action_type {
-        return true;
-    }
-    let organ_type = if ActionType::Growth == action_type {
-        get_organ_type(action)
-    } else {
-        OrganType::Root
+    let organ_type = match action_type {
+        ActionType::Wait => return true,
+        ActionType::Growth => get_organ_type(action),
+        ActionType::Sporer => OrganType::Root,
     };
-    protein_wallet::can_buy_organ(player.get_wallet(), organ_type)
+    let is_placed = match action_type {
+        ActionType::Growth => organ_type != OrganType::Root,
+        _ => actions_finder::find_sporer(grid, player.get_id(), action).is_some(),
+    };
+    is_placed
+        && protein_wallet::can_buy_organ(player.get_wallet(), organ_type)
         && grid.can_add_organ_with_root_coord(
             coord_target,
             organ::new(player.get_id(), organ_type, direction, coord_root),
@@ -283,4 +290,46 @@ mod tests {
         );
         assert!(is_valid(action, &grid, &player));
     }
+
+    #[test]
+    fn test_is_valid_with_root_follows_the_referee() {
+        let mut player = Player::new(0);
+        player.add_protein(Protein::A, 1);
+        player.add_protein(Protein::B, 1);
+        player.add_protein(Protein::C, 1);
+        player.add_protein(Protein::D, 1);
+        let mut grid = Grid::new(10, 10);
+        let root = coord::new(2, 2);
+        grid.set_cell(
+            2,
+            2,
+            cell::new(
+                false,
+                None,
+                Some(organ::new(0, OrganType::Root, OrganDirection::North, root)),
+            ),
+        );
+        let grow_root = growth(
+            OrganType::Root,
+            OrganDirection::North,
+            coord::new(2, 3),
+            root,
+        );
+        let spore = sporer(OrganDirection::East, coord::new(5, 2), root);
+
+        assert!(is_valid(grow_root, &grid, &player));
+        assert!(!is_valid_with_root(grow_root, &grid, &player));
+        // no sporer faces the target
+        assert!(!is_valid_with_root(spore, &grid, &player));
+        grid.set_cell(
+            3,
+            2,
+            cell::new(
+                false,
+                None,
+                Some(organ::new(0, OrganType::Sporer, OrganDirection::East, root)),
+            ),
+        );
+        assert!(is_valid_with_root(spore, &grid, &player));
+    }
 }
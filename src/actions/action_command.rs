@@ -0,0 +1,148 @@
+use crate::game_entities::{
+    cell,
+    coord::{self, Coord},
+    game_state::GameState,
+    organ,
+};
+
+use super::{
+    action::{self, Action},
+    action_type::ActionType,
+};
+
+/// Referee command for `action`, the organ ids are resolved from `state`.
+pub fn to_command(action: Action, state: &GameState) -> String {
+    let target = action::get_coord_target(action);
+    let root_coord = action::get_coord_source(action);
+    match action::get_type(action) {
+        ActionType::Wait => "WAIT".to_string(),
+        ActionType::Growth => format!(
+            "GROW {} {} {} {} {}",
+            get_parent_id(state, target, root_coord),
+            coord::x(target),
+            coord::y(target),
+            action::get_organ_type(action).to_str(),
+            action::get_direction(action).to_char()
+        ),
+        ActionType::Sporer => format!(
+            "SPORE {} {} {}",
+            get_sporer_id(state, action),
+            coord::x(target),
+            coord::y(target)
+        ),
+    }
+}
+
+fn get_root_id(state: &GameState, root_coord: Coord) -> i32 {
+    state.get_organ_id(root_coord).unwrap_or(0)
+}
+
+/// The parent the simulator attaches the new organ to, so both organ trees stay the same.
+fn get_parent_id(state: &GameState, target: Coord, root_coord: Coord) -> i32 {
+    let root_cell = state.grid.get_cell_from_coord(root_coord);
+    cell::is_organ(root_cell)
+        .then(|| organ::get_owner(cell::get_organ(root_cell).unwrap()))
+        .and_then(|owner| state.grid.find_parent(target, owner, root_coord))
+        .and_then(|parent| state.get_organ_id(parent))
+        .unwrap_or_else(|| get_root_id(state, root_coord))
+}
+
+fn get_sporer_id(state: &GameState, action: Action) -> i32 {
+    let root_coord = action::get_coord_source(action);
+    let direction = action::get_direction(action);
+    let mut current = state
+        .grid
+        .get_next_coord(action::get_coord_target(action), direction.opposite());
+    while let Some(coord) = current {
+        let cell = state.grid.get_cell_from_coord(coord);
+        if cell::has_root_coord(cell, root_coord) {
+            let organ = cell::get_organ(cell).unwrap();
+            if organ::is_sporer(organ) && organ::get_direction(organ) == direction {
+                return state.get_organ_id(coord).unwrap_or(0);
+            }
+        }
+        current = state.grid.get_next_coord(coord, direction.opposite());
+    }
+    get_root_id(state, root_coord)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::{
+        game_state::Entity, organ_direction::OrganDirection, organ_type::OrganType,
+    };
+
+    use super::*;
+
+    fn build_state() -> GameState {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "1 1 BASIC 1 2 E 1 1",
+            "2 1 SPORER 1 3 E 2 1",
+            "9 1 ROOT 0 4 N 0 4",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(10, 3, &entities, [0, 0], 1)
+    }
+
+    #[test]
+    fn test_wait_command() {
+        assert_eq!(to_command(action::wait(), &build_state()), "WAIT");
+    }
+
+    #[test]
+    fn test_grow_command_uses_adjacent_organ() {
+        let state = build_state();
+        let root = coord::new(0, 1);
+
+        let action = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            coord::new(1, 0),
+            root,
+        );
+        assert_eq!(to_command(action, &state), "GROW 2 1 0 BASIC N");
+
+        let action = action::growth(
+            OrganType::Harvester,
+            OrganDirection::East,
+            coord::new(5, 0),
+            root,
+        );
+        assert_eq!(to_command(action, &state), "GROW 1 5 0 HARVESTER E");
+    }
+
+    #[test]
+    fn test_grow_command_names_the_simulated_parent() {
+        let entities: Vec<Entity> = [
+            "2 1 ROOT 1 1 N 0 1",
+            "3 1 BASIC 1 2 E 1 1",
+            "3 2 BASIC 1 3 S 2 1",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        let mut state = GameState::from_entities(6, 4, &entities, [0, 0], 1);
+        let root = coord::new(2, 1);
+        let target = coord::new(2, 2);
+        let action = action::growth(OrganType::Basic, OrganDirection::North, target, root);
+
+        // the root has the smallest id but the east organ comes first
+        assert_eq!(to_command(action, &state), "GROW 3 2 2 BASIC N");
+        state.add_organ(
+            target,
+            organ::new(1, OrganType::Basic, OrganDirection::North, root),
+        );
+        assert_eq!(state.grid.get_parent(target), Some(coord::new(3, 2)));
+    }
+
+    #[test]
+    fn test_spore_command_uses_sporer() {
+        let state = build_state();
+        let action = action::sporer(OrganDirection::East, coord::new(6, 1), coord::new(0, 1));
+
+        assert_eq!(to_command(action, &state), "SPORE 3 6 1");
+    }
+}
@@ -6,6 +6,7 @@ pub enum ActionType {
 }
 
 impl ActionType {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> ActionType {
         match s {
             "WAIT" => ActionType::Wait,
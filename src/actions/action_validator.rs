@@ -1,19 +1,28 @@
+use rand::Rng;
+
 use crate::game_entities::{
     coord, grid::Grid, organ, organ_direction::OrganDirection, organ_type::OrganType,
 };
 
 use super::action;
 
-pub fn make_growth_valid(action: action::Action, grid: &Grid) -> action::Action {
+/// Moves the target of a GROW of `owner` on its row or column until it is legal, the random
+/// organ type of a GROW ROOT being drawn from `rng`.
+pub fn make_growth_valid(
+    action: action::Action,
+    grid: &Grid,
+    owner: u8,
+    rng: &mut impl Rng,
+) -> action::Action {
     let x_coord = coord::x(action::get_coord_target(action));
     let y_coord = coord::y(action::get_coord_target(action));
     let mut result = action;
     if action::get_organ_type(action) == OrganType::Root {
-        let organ_type = (rand::random::<usize>()) % 0b100;
+        let organ_type = rng.gen_range(0..0b100);
         result = action::set_organ_type(result, OrganType::from_index(organ_type));
     }
     let organ = organ::new(
-        0,
+        owner,
         action::get_organ_type(result),
         action::get_direction(result),
         action::get_coord_source(result),
@@ -35,6 +44,7 @@ pub fn make_sporer_valid(
     action_last_sporer_creation: action::Action,
     action: action::Action,
     grid: &Grid,
+    rng: &mut impl Rng,
 ) -> action::Action {
     let x_coord = coord::x(action::get_coord_target(action_last_sporer_creation));
     let y_coord = coord::y(action::get_coord_target(action_last_sporer_creation));
@@ -51,28 +61,28 @@ pub fn make_sporer_valid(
             if x_coord >= grid.width - 1 {
                 return action::wait();
             }
-            let adition_on_x = (rand::random::<u8>() % (grid.width - 1 - x_coord)) + 1;
+            let adition_on_x = rng.gen_range(0..(grid.width - 1 - x_coord)) + 1;
             action::set_coord_target(result, coord::new(x_coord + adition_on_x, y_coord))
         }
         OrganDirection::West => {
             if x_coord == 0 {
                 return action::wait();
             }
-            let substraction_on_x = (rand::random::<u8>() % x_coord) + 1;
+            let substraction_on_x = rng.gen_range(0..x_coord) + 1;
             action::set_coord_target(result, coord::new(x_coord - substraction_on_x, y_coord))
         }
         OrganDirection::North => {
             if y_coord == 0 {
                 return action::wait();
             }
-            let substraction_on_y = (rand::random::<u8>() % y_coord) + 1;
+            let substraction_on_y = rng.gen_range(0..y_coord) + 1;
             action::set_coord_target(result, coord::new(x_coord, y_coord - substraction_on_y))
         }
         OrganDirection::South => {
             if y_coord >= grid.height - 1 {
                 return action::wait();
             }
-            let adition_on_y = (rand::random::<u8>() % (grid.height - 1 - y_coord)) + 1;
+            let adition_on_y = rng.gen_range(0..(grid.height - 1 - y_coord)) + 1;
             action::set_coord_target(result, coord::new(x_coord, y_coord + adition_on_y))
         }
         _ => action::wait(),
@@ -86,6 +96,11 @@ mod tests {
         actions::action_type::ActionType,
         game_entities::{cell, organ_direction::OrganDirection, organ_type::OrganType},
     };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(0)
+    }
 
     #[test]
     fn test_make_it_valid() {
@@ -96,7 +111,7 @@ mod tests {
             coord::new(0, 0),
             coord::new(0, 0),
         );
-        let valid_action = make_growth_valid(action, &grid);
+        let valid_action = make_growth_valid(action, &grid, 0, &mut rng());
         assert_eq!(action::get_type(valid_action), ActionType::Wait);
     }
 
@@ -121,7 +136,7 @@ mod tests {
             coord::new(0, 0),
             coord::new(0, 0),
         );
-        let valid_action = make_growth_valid(action, &grid);
+        let valid_action = make_growth_valid(action, &grid, 0, &mut rng());
         assert_eq!(action::get_type(valid_action), ActionType::Growth);
         assert_eq!(action::get_direction(valid_action), OrganDirection::South);
         assert_eq!(action::get_coord_target(valid_action), coord::new(0, 3));
@@ -136,7 +151,7 @@ mod tests {
             coord::new(0, 0),
             coord::new(0, 0),
         );
-        let valid_action = make_growth_valid(action, &grid);
+        let valid_action = make_growth_valid(action, &grid, 0, &mut rng());
         assert_eq!(action::get_type(valid_action), ActionType::Wait);
     }
 
@@ -161,18 +176,61 @@ mod tests {
             coord::new(0, 0),
             coord::new(0, 0),
         );
-        let valid_action = make_growth_valid(action, &grid);
+        let valid_action = make_growth_valid(action, &grid, 0, &mut rng());
         assert_eq!(action::get_type(valid_action), ActionType::Growth);
         assert_eq!(action::get_direction(valid_action), OrganDirection::East);
         assert_eq!(action::get_coord_target(valid_action), coord::new(3, 0));
     }
 
+    #[test]
+    fn test_make_growth_valid_checks_the_tentacles_of_the_other_player() {
+        let mut grid = Grid::new(5, 5);
+        let root_coord = coord::new(1, 2);
+        grid.set_cell(
+            1,
+            2,
+            cell::new(
+                false,
+                None,
+                Some(organ::new(
+                    1,
+                    OrganType::Root,
+                    OrganDirection::X,
+                    root_coord,
+                )),
+            ),
+        );
+        grid.set_cell(
+            2,
+            1,
+            cell::new(
+                false,
+                None,
+                Some(organ::new(
+                    0,
+                    OrganType::Tentacle,
+                    OrganDirection::West,
+                    coord::new(4, 1),
+                )),
+            ),
+        );
+        let action = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            coord::new(0, 1),
+            root_coord,
+        );
+        let valid_action = make_growth_valid(action, &grid, 1, &mut rng());
+        // (1, 1) is in front of the tentacle of player 0
+        assert_eq!(action::get_coord_target(valid_action), coord::new(0, 2));
+    }
+
     #[test]
     fn test_make_sporer_valid_should_false_beceause_on_edge() {
         let grid = Grid::new(5, 5);
         let last_action = action::sporer(OrganDirection::East, coord::new(4, 4), coord::new(0, 0));
         let action = action::sporer(OrganDirection::East, coord::new(4, 4), coord::new(0, 0));
-        let valid_action = make_sporer_valid(last_action, action, &grid);
+        let valid_action = make_sporer_valid(last_action, action, &grid, &mut rng());
         assert_eq!(action::get_type(valid_action), ActionType::Wait);
     }
 
@@ -181,7 +239,7 @@ mod tests {
         let grid = Grid::new(5, 5);
         let last_action = action::sporer(OrganDirection::West, coord::new(0, 0), coord::new(0, 0));
         let action = action::sporer(OrganDirection::West, coord::new(0, 0), coord::new(0, 0));
-        let valid_action = make_sporer_valid(last_action, action, &grid);
+        let valid_action = make_sporer_valid(last_action, action, &grid, &mut rng());
         assert_eq!(action::get_type(valid_action), ActionType::Wait);
     }
 
@@ -190,7 +248,7 @@ mod tests {
         let grid = Grid::new(5, 5);
         let last_action = action::sporer(OrganDirection::North, coord::new(0, 0), coord::new(0, 0));
         let action = action::sporer(OrganDirection::North, coord::new(0, 0), coord::new(0, 0));
-        let valid_action = make_sporer_valid(last_action, action, &grid);
+        let valid_action = make_sporer_valid(last_action, action, &grid, &mut rng());
         assert_eq!(action::get_type(valid_action), ActionType::Wait);
     }
 
@@ -199,7 +257,7 @@ mod tests {
         let grid = Grid::new(5, 5);
         let last_action = action::sporer(OrganDirection::South, coord::new(4, 4), coord::new(0, 0));
         let action = action::sporer(OrganDirection::South, coord::new(4, 4), coord::new(0, 0));
-        let valid_action = make_sporer_valid(last_action, action, &grid);
+        let valid_action = make_sporer_valid(last_action, action, &grid, &mut rng());
         assert_eq!(action::get_type(valid_action), ActionType::Wait);
     }
 
@@ -208,7 +266,7 @@ mod tests {
         let grid = Grid::new(5, 5);
         let last_action = action::sporer(OrganDirection::East, coord::new(3, 3), coord::new(0, 0));
         let action = action::sporer(OrganDirection::East, coord::new(0, 0), coord::new(0, 0));
-        let valid_action = make_sporer_valid(last_action, action, &grid);
+        let valid_action = make_sporer_valid(last_action, action, &grid, &mut rng());
         assert_eq!(action::get_type(valid_action), ActionType::Sporer);
         assert_eq!(action::get_direction(valid_action), OrganDirection::East);
         assert_eq!(action::get_coord_target(valid_action), coord::new(4, 3));
@@ -219,7 +277,7 @@ mod tests {
         let grid = Grid::new(5, 5);
         let last_action = action::sporer(OrganDirection::West, coord::new(1, 1), coord::new(0, 0));
         let action = action::sporer(OrganDirection::West, coord::new(0, 0), coord::new(0, 0));
-        let valid_action = make_sporer_valid(last_action, action, &grid);
+        let valid_action = make_sporer_valid(last_action, action, &grid, &mut rng());
         assert_eq!(action::get_type(valid_action), ActionType::Sporer);
         assert_eq!(action::get_direction(valid_action), OrganDirection::West);
         assert_eq!(action::get_coord_target(valid_action), coord::new(0, 1));
@@ -230,7 +288,7 @@ mod tests {
         let grid = Grid::new(5, 5);
         let last_action = action::sporer(OrganDirection::North, coord::new(1, 1), coord::new(0, 0));
         let action = action::sporer(OrganDirection::North, coord::new(0, 0), coord::new(0, 0));
-        let valid_action = make_sporer_valid(last_action, action, &grid);
+        let valid_action = make_sporer_valid(last_action, action, &grid, &mut rng());
         assert_eq!(action::get_type(valid_action), ActionType::Sporer);
         assert_eq!(action::get_direction(valid_action), OrganDirection::North);
         assert_eq!(action::get_coord_target(valid_action), coord::new(1, 0));
@@ -241,7 +299,7 @@ mod tests {
         let grid = Grid::new(5, 5);
         let last_action = action::sporer(OrganDirection::South, coord::new(1, 3), coord::new(0, 0));
         let action = action::sporer(OrganDirection::South, coord::new(0, 0), coord::new(0, 0));
-        let valid_action = make_sporer_valid(last_action, action, &grid);
+        let valid_action = make_sporer_valid(last_action, action, &grid, &mut rng());
         assert_eq!(action::get_type(valid_action), ActionType::Sporer);
         assert_eq!(action::get_direction(valid_action), OrganDirection::South);
         assert_eq!(action::get_coord_target(valid_action), coord::new(1, 4));
@@ -0,0 +1,355 @@
+use crate::{
+    game_entities::{
+        coord::Coord,
+        game_state::GameState,
+        organ,
+        protein_wallet::{self, ProteinWallet},
+    },
+    simulation::turn_simulator,
+    strategies::play,
+};
+
+use super::{
+    action::{self, Action},
+    action_type::ActionType,
+    actions_finder,
+};
+
+/// Candidates kept per root by `optimise`, the search is exponential in it.
+pub const MAX_CANDIDATES_PER_ROOT: usize = 6;
+
+/// Shared wallet and targets already taken by the roots handled so far.
+#[derive(Clone)]
+struct Allocation {
+    wallet: ProteinWallet,
+    targets: Vec<Coord>,
+}
+
+impl Allocation {
+    fn new(state: &GameState, owner: u8) -> Self {
+        Allocation {
+            wallet: state.players[owner as usize].get_wallet(),
+            targets: Vec::new(),
+        }
+    }
+
+    /// Pays for `action` when it is playable with what is left.
+    fn try_take(&mut self, state: &GameState, owner: u8, action: Action) -> bool {
+        if action::get_type(action) == ActionType::Wait {
+            return true;
+        }
+        let target = action::get_coord_target(action);
+        let organ_type = match turn_simulator::get_placed_organ(&state.grid, owner, action) {
+            Some(organ) => organ::get_type(organ),
+            None => return false,
+        };
+        if self.targets.contains(&target) || !protein_wallet::can_buy_organ(self.wallet, organ_type)
+        {
+            return false;
+        }
+        protein_wallet::buy_organ(&mut self.wallet, organ_type);
+        self.targets.push(target);
+        true
+    }
+}
+
+/// One action per root in root id order: the first candidate of each root that is
+/// still affordable and whose target is not taken by a previous root, WAIT otherwise.
+pub fn coordinate(state: &GameState, owner: u8, candidates: &[Vec<Action>]) -> Vec<Action> {
+    let mut allocation = Allocation::new(state, owner);
+    state
+        .get_roots_in_order(owner)
+        .iter()
+        .enumerate()
+        .map(|(index, _)| {
+            candidates
+                .get(index)
+                .and_then(|root_candidates| {
+                    root_candidates
+                        .iter()
+                        .copied()
+                        .find(|&candidate| allocation.try_take(state, owner, candidate))
+                })
+                .unwrap_or_else(action::wait)
+        })
+        .collect()
+}
+
+/// Makes the actions of a strategy consistent, each root keeping its own action or waiting.
+pub fn make_consistent(state: &GameState, owner: u8, actions: &[Action]) -> Vec<Action> {
+    let candidates: Vec<Vec<Action>> = actions.iter().map(|&action| vec![action]).collect();
+    coordinate(state, owner, &candidates)
+}
+
+/// Last check before printing: each action must be valid with `action::is_valid_with_root`,
+/// belong to its root and be affordable. The valid actions are paid first, then an invalid
+/// one is replaced by the greedy action of its root, or else the first action of the move
+/// generator, that the proteins left pay for, WAIT otherwise.
+/// Returns the secured actions and the rejected ones.
+pub fn secure(state: &GameState, owner: u8, actions: &[Action]) -> (Vec<Action>, Vec<Action>) {
+    let player = &state.players[owner as usize];
+    let mut allocation = Allocation::new(state, owner);
+    let mut is_playable = |candidate: Action, root: Coord| {
+        action::get_type(candidate) == ActionType::Wait
+            || (action::get_coord_source(candidate) == root
+                && action::is_valid_with_root(candidate, &state.grid, player)
+                && allocation.try_take(state, owner, candidate))
+    };
+    let roots = state.get_roots_in_order(owner);
+    let kept: Vec<Option<Action>> = roots
+        .iter()
+        .enumerate()
+        .map(|(index, &root)| {
+            let chosen = actions.get(index).copied().unwrap_or_else(action::wait);
+            is_playable(chosen, root).then_some(chosen)
+        })
+        .collect();
+    let mut rejected = Vec::new();
+    let secured = kept
+        .into_iter()
+        .zip(roots)
+        .enumerate()
+        .map(|(index, (kept, root))| {
+            kept.unwrap_or_else(|| {
+                rejected.push(actions[index]);
+                let greedy = play::found_an_action(
+                    &state.grid,
+                    player,
+                    &state.players[1 - owner as usize],
+                    root,
+                );
+                std::iter::once(greedy)
+                    .chain(actions_finder::find_all_actions(&state.grid, player, root))
+                    .find(|&fallback| fallback != action::wait() && is_playable(fallback, root))
+                    .unwrap_or_else(action::wait)
+            })
+        })
+        .collect();
+    (secured, rejected)
+}
+
+/// Joint allocation maximising the sum of the candidate values, by branch and bound
+/// over the best `MAX_CANDIDATES_PER_ROOT` candidates of each root.
+pub fn optimise(state: &GameState, owner: u8, candidates: &[Vec<(Action, f64)>]) -> Vec<Action> {
+    let nb_roots = state.get_roots_in_order(owner).len();
+    let sorted: Vec<Vec<(Action, f64)>> = (0..nb_roots)
+        .map(|index| {
+            let mut root_candidates = candidates.get(index).cloned().unwrap_or_default();
+            root_candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+            root_candidates.truncate(MAX_CANDIDATES_PER_ROOT);
+            root_candidates.push((action::wait(), 0.0));
+            root_candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+            root_candidates
+        })
+        .collect();
+    let mut best_remaining = vec![0.0; nb_roots + 1];
+    for index in (0..nb_roots).rev() {
+        best_remaining[index] = best_remaining[index + 1] + sorted[index][0].1.max(0.0);
+    }
+    let mut search = JointSearch {
+        state,
+        owner,
+        candidates: &sorted,
+        best_remaining: &best_remaining,
+        current: Vec::with_capacity(nb_roots),
+        best: vec![action::wait(); nb_roots],
+        best_value: 0.0,
+    };
+    search.explore(&Allocation::new(state, owner), 0.0);
+    search.best
+}
+
+struct JointSearch<'a> {
+    state: &'a GameState,
+    owner: u8,
+    candidates: &'a [Vec<(Action, f64)>],
+    best_remaining: &'a [f64],
+    current: Vec<Action>,
+    best: Vec<Action>,
+    best_value: f64,
+}
+
+impl<'a> JointSearch<'a> {
+    fn explore(&mut self, allocation: &Allocation, value: f64) {
+        let index = self.current.len();
+        if index == self.candidates.len() {
+            if value > self.best_value {
+                self.best_value = value;
+                self.best = self.current.clone();
+            }
+            return;
+        }
+        if value + self.best_remaining[index] <= self.best_value {
+            return;
+        }
+        for &(candidate, candidate_value) in self.candidates[index].iter() {
+            let mut next = allocation.clone();
+            if next.try_take(self.state, self.owner, candidate) {
+                self.current.push(candidate);
+                self.explore(&next, value + candidate_value);
+                self.current.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::{
+        coord,
+        game_state::{wallet_from_counts, Entity, MY_ID},
+        organ_direction::OrganDirection,
+        organ_type::OrganType,
+        protein::Protein,
+    };
+
+    use super::*;
+
+    fn build_state(a: u32) -> GameState {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "2 1 ROOT 1 2 N 0 2",
+            "6 1 ROOT 0 3 N 0 3",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(7, 3, &entities, [0, wallet_from_counts(a, 0, 0, 0)], 2)
+    }
+
+    fn basic(x: u8, y: u8, root: Coord) -> Action {
+        action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            coord::new(x, y),
+            root,
+        )
+    }
+
+    #[test]
+    fn test_coordinate_forbids_duplicate_targets() {
+        let state = build_state(5);
+        let first_root = coord::new(0, 1);
+        let second_root = coord::new(2, 1);
+        let candidates = vec![
+            vec![basic(1, 1, first_root)],
+            vec![basic(1, 1, second_root), basic(3, 1, second_root)],
+        ];
+
+        assert_eq!(
+            coordinate(&state, MY_ID, &candidates),
+            vec![basic(1, 1, first_root), basic(3, 1, second_root)]
+        );
+    }
+
+    #[test]
+    fn test_coordinate_deducts_costs() {
+        let state = build_state(1);
+        let candidates = vec![
+            vec![basic(1, 1, coord::new(0, 1))],
+            vec![basic(3, 1, coord::new(2, 1))],
+        ];
+        let actions = coordinate(&state, MY_ID, &candidates);
+
+        assert_eq!(actions[1], action::wait());
+        assert_eq!(state.get_me().get_nb_protein(Protein::A), 1);
+    }
+
+    #[test]
+    fn test_make_consistent_pads_and_drops_invalid() {
+        let state = build_state(5);
+        let invalid = basic(5, 1, coord::new(0, 1));
+
+        assert_eq!(
+            make_consistent(&state, MY_ID, &[invalid]),
+            vec![action::wait(), action::wait()]
+        );
+    }
+
+    #[test]
+    fn test_optimise_prefers_the_best_joint_allocation() {
+        let state = build_state(2);
+        let first_root = coord::new(0, 1);
+        let second_root = coord::new(2, 1);
+        // greedily the first root would take (1, 1) and the second root could only get 1.0
+        let candidates = vec![
+            vec![
+                (basic(1, 1, first_root), 3.0),
+                (basic(0, 0, first_root), 2.5),
+            ],
+            vec![
+                (basic(1, 1, second_root), 4.0),
+                (basic(3, 1, second_root), 1.0),
+            ],
+        ];
+
+        assert_eq!(
+            optimise(&state, MY_ID, &candidates),
+            vec![basic(0, 0, first_root), basic(1, 1, second_root)]
+        );
+    }
+
+    #[test]
+    fn test_optimise_respects_the_wallet() {
+        let state = build_state(1);
+        let candidates = vec![
+            vec![(basic(0, 0, coord::new(0, 1)), 1.0)],
+            vec![(basic(3, 1, coord::new(2, 1)), 2.0)],
+        ];
+
+        assert_eq!(
+            optimise(&state, MY_ID, &candidates),
+            vec![action::wait(), basic(3, 1, coord::new(2, 1))]
+        );
+    }
+
+    #[test]
+    fn test_secure_keeps_valid_actions() {
+        let state = build_state(5);
+        let actions = vec![basic(1, 1, coord::new(0, 1)), action::wait()];
+
+        assert_eq!(secure(&state, MY_ID, &actions), (actions, Vec::new()));
+    }
+
+    #[test]
+    fn test_secure_replaces_invalid_actions() {
+        let state = build_state(2);
+        let first_root = coord::new(0, 1);
+        let grow_root = action::growth(
+            OrganType::Root,
+            OrganDirection::North,
+            coord::new(0, 0),
+            first_root,
+        );
+        let (secured, rejected) = secure(&state, MY_ID, &[grow_root, action::wait()]);
+
+        assert_eq!(rejected, vec![grow_root]);
+        assert_eq!(secured.len(), 2);
+        assert_ne!(secured[0], action::wait());
+        assert!(action::is_valid_with_root(
+            secured[0],
+            &state.grid,
+            state.get_me()
+        ));
+        assert_eq!(action::get_coord_source(secured[0]), first_root);
+        assert_eq!(secured[1], action::wait());
+    }
+
+    #[test]
+    fn test_secure_pays_the_valid_actions_before_the_fallbacks() {
+        let state = build_state(1);
+        let first_root = coord::new(0, 1);
+        let second_root = coord::new(2, 1);
+        let grow_root = action::growth(
+            OrganType::Root,
+            OrganDirection::North,
+            coord::new(0, 0),
+            first_root,
+        );
+        // the only protein goes to the valid action of the second root
+        let (secured, rejected) = secure(&state, MY_ID, &[grow_root, basic(3, 1, second_root)]);
+
+        assert_eq!(rejected, vec![grow_root]);
+        assert_eq!(secured, vec![action::wait(), basic(3, 1, second_root)]);
+    }
+}
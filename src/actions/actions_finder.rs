@@ -1 +1,191 @@
+use crate::game_entities::{
+    cell, coord::Coord, grid::Grid, organ, organ_direction::OrganDirection, organ_type::OrganType,
+    player::Player,
+};
 
+use super::action::{self, Action};
+
+pub const GROWABLE_TYPES: [OrganType; 4] = [
+    OrganType::Basic,
+    OrganType::Harvester,
+    OrganType::Sporer,
+    OrganType::Tentacle,
+];
+
+pub const DIRECTIONS: [OrganDirection; 4] = [
+    OrganDirection::North,
+    OrganDirection::West,
+    OrganDirection::South,
+    OrganDirection::East,
+];
+
+/// Every affordable GROW of the organism rooted at `root_coord`.
+pub fn find_growth_actions(grid: &Grid, player: &Player, root_coord: Coord) -> Vec<Action> {
+    let affordable_types: Vec<OrganType> = GROWABLE_TYPES
+        .iter()
+        .copied()
+        .filter(|&organ_type| player.can_buy(organ_type))
+        .collect();
+    if affordable_types.is_empty() {
+        return Vec::new();
+    }
+    let mut actions = Vec::new();
+    for coord in grid.iter_root_frontier(player.get_id(), root_coord) {
+        for &organ_type in affordable_types.iter() {
+            for &direction in DIRECTIONS.iter() {
+                actions.push(action::growth(organ_type, direction, coord, root_coord));
+            }
+        }
+    }
+    actions
+}
+
+/// Every SPORE target reachable from the sporers of the organism rooted at `root_coord`.
+pub fn find_spore_actions(grid: &Grid, player: &Player, root_coord: Coord) -> Vec<Action> {
+    if !player.can_buy(OrganType::Root) {
+        return Vec::new();
+    }
+    let new_root = organ::new(player.get_id(), OrganType::Root, OrganDirection::North, 0);
+    let mut actions = Vec::new();
+    let sporers = grid
+        .iter_organs_of_root(root_coord)
+        .filter(|organ| organ.owner == player.get_id() && organ.organ_type == OrganType::Sporer);
+    for sporer in sporers {
+        let mut current = grid.get_next_coord(sporer.coord, sporer.direction);
+        while let Some(target) = current {
+            if !cell::is_growable(grid.get_cell_from_coord(target)) {
+                break;
+            }
+            if grid.can_add_organ_without_root_coord(target, new_root) {
+                actions.push(action::sporer(sporer.direction, target, root_coord));
+            }
+            current = grid.get_next_coord(target, sporer.direction);
+        }
+    }
+    actions
+}
+
+/// Sporer of the organism able to shoot the SPORE `action`, None when the line is blocked.
+pub fn find_sporer(grid: &Grid, owner: u8, action: Action) -> Option<Coord> {
+    let root_coord = action::get_coord_source(action);
+    let direction = action::get_direction(action);
+    let mut current = grid.get_next_coord(action::get_coord_target(action), direction.opposite());
+    while let Some(coord) = current {
+        let cell = grid.get_cell_from_coord(coord);
+        if !cell::is_growable(cell) {
+            let is_sporer = cell::is_owned_and_rooted_by(cell, owner, root_coord)
+                && organ::is_sporer(cell::get_organ(cell).unwrap())
+                && organ::get_direction(cell::get_organ(cell).unwrap()) == direction;
+            return if is_sporer { Some(coord) } else { None };
+        }
+        current = grid.get_next_coord(coord, direction.opposite());
+    }
+    None
+}
+
+/// WAIT followed by every legal GROW and SPORE of the organism rooted at `root_coord`.
+pub fn find_all_actions(grid: &Grid, player: &Player, root_coord: Coord) -> Vec<Action> {
+    let mut actions = vec![action::wait()];
+    actions.extend(find_growth_actions(grid, player, root_coord));
+    actions.extend(find_spore_actions(grid, player, root_coord));
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        actions::action_type::ActionType,
+        game_entities::{coord, protein::Protein},
+    };
+
+    use super::*;
+
+    fn build_grid() -> Grid {
+        let mut grid = Grid::new(6, 3);
+        let root = coord::new(0, 1);
+        grid.add_organ(
+            root,
+            organ::new(0, OrganType::Root, OrganDirection::North, root),
+        );
+        grid.add_organ(
+            coord::new(1, 1),
+            organ::new(0, OrganType::Sporer, OrganDirection::East, root),
+        );
+        grid.set_cell(4, 1, cell::new_wall());
+        grid.set_cell(1, 0, cell::new(false, Some(Protein::A), None));
+        grid
+    }
+
+    fn player_with(amount: u32) -> Player {
+        let mut player = Player::new(0);
+        for protein in [Protein::A, Protein::B, Protein::C, Protein::D] {
+            player.add_protein(protein, amount);
+        }
+        player
+    }
+
+    #[test]
+    fn test_find_all_actions_count() {
+        let grid = build_grid();
+        let root = coord::new(0, 1);
+        let actions = find_all_actions(&grid, &player_with(1), root);
+
+        // 5 frontier cells * 4 types * 4 directions, 2 spore targets and WAIT
+        assert_eq!(actions.len(), 5 * 4 * 4 + 2 + 1);
+        assert_eq!(action::get_type(actions[0]), ActionType::Wait);
+    }
+
+    #[test]
+    fn test_every_action_is_valid() {
+        let grid = build_grid();
+        let root = coord::new(0, 1);
+        let player = player_with(1);
+
+        for action in find_all_actions(&grid, &player, root) {
+            assert!(action::is_valid_with_root(action, &grid, &player));
+        }
+    }
+
+    #[test]
+    fn test_find_spore_actions_stop_at_wall() {
+        let grid = build_grid();
+        let root = coord::new(0, 1);
+        let targets: Vec<Coord> = find_spore_actions(&grid, &player_with(1), root)
+            .into_iter()
+            .map(action::get_coord_target)
+            .collect();
+
+        assert_eq!(targets, vec![coord::new(2, 1), coord::new(3, 1)]);
+    }
+
+    #[test]
+    fn test_find_sporer() {
+        let grid = build_grid();
+        let root = coord::new(0, 1);
+        let spore = action::sporer(OrganDirection::East, coord::new(3, 1), root);
+
+        assert_eq!(find_sporer(&grid, 0, spore), Some(coord::new(1, 1)));
+        assert_eq!(find_sporer(&grid, 1, spore), None);
+        let spore = action::sporer(OrganDirection::East, coord::new(5, 1), root);
+        assert_eq!(find_sporer(&grid, 0, spore), None);
+        let spore = action::sporer(OrganDirection::West, coord::new(0, 0), root);
+        assert_eq!(find_sporer(&grid, 0, spore), None);
+    }
+
+    #[test]
+    fn test_only_affordable_actions() {
+        let grid = build_grid();
+        let root = coord::new(0, 1);
+        let mut player = Player::new(0);
+
+        assert_eq!(find_all_actions(&grid, &player, root), vec![action::wait()]);
+
+        player.add_protein(Protein::A, 1);
+        let actions = find_growth_actions(&grid, &player, root);
+        assert_eq!(actions.len(), 5 * 4);
+        assert!(actions
+            .iter()
+            .all(|&action| action::get_organ_type(action) == OrganType::Basic));
+        assert!(find_spore_actions(&grid, &player, root).is_empty());
+    }
+}
@@ -1,4 +1,6 @@
 pub mod action;
+pub mod action_command;
 pub mod action_type;
 pub mod action_validator;
+pub mod actions_coordinator;
 pub mod actions_finder;
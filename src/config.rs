@@ -0,0 +1,255 @@
+use std::{fs, str::FromStr, time::Duration};
+
+use crate::{
+    simulation::evaluation::EvaluationParams,
+    strategies::{registry, self_play::SelfPlayConfig, time_manager::TURN_BUDGET},
+};
+
+/// Environment variable read for each option, the command line wins over it.
+pub const OPTIONS: [(&str, &str); 9] = [
+    ("--strategy", "BOT_STRATEGY"),
+    ("--time-ms", "BOT_TIME_MS"),
+    ("--seed", "BOT_SEED"),
+    ("--weights", "BOT_WEIGHTS"),
+    ("--log-level", "BOT_LOG_LEVEL"),
+    ("--self-play", "BOT_SELF_PLAY"),
+    ("--opponent", "BOT_OPPONENT"),
+    ("--output", "BOT_OUTPUT"),
+    ("--checks", "BOT_CHECKS"),
+];
+
+pub const DEFAULT_OUTPUT: &str = "self_play.jsonl";
+
+pub const DEFAULT_CHECKS: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Off,
+    /// What the bot deduces of the opponent each turn.
+    Info,
+    /// The config and the time spent each turn too.
+    Debug,
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "off" => Ok(LogLevel::Off),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            _ => Err(format!("Unknown log level {}", name)),
+        }
+    }
+}
+
+/// Options of the bot binary, the defaults being what is submitted to CodinGame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BotConfig {
+    pub strategy: String,
+    /// Budget of every turn but the first.
+    pub turn_budget: Duration,
+    pub seed: u64,
+    pub evaluation: EvaluationParams,
+    pub log_level: LogLevel,
+    /// Self-play games to generate instead of playing on stdin, none by default.
+    pub self_play_games: usize,
+    /// Strategy of player 0 in self-play, `strategy` playing player 1.
+    pub opponent: String,
+    pub output: String,
+    pub checks_per_turn: u32,
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        BotConfig {
+            strategy: registry::DEFAULT_STRATEGY.to_string(),
+            turn_budget: TURN_BUDGET,
+            seed: 0,
+            evaluation: EvaluationParams::default(),
+            log_level: LogLevel::Info,
+            self_play_games: 0,
+            opponent: registry::DEFAULT_STRATEGY.to_string(),
+            output: DEFAULT_OUTPUT.to_string(),
+            checks_per_turn: DEFAULT_CHECKS,
+        }
+    }
+}
+
+impl BotConfig {
+    /// Config of the running process.
+    pub fn from_env() -> Result<Self, String> {
+        BotConfig::parse(std::env::args().skip(1), |name| std::env::var(name).ok())
+    }
+
+    /// Environment variables then `--option value` arguments, a lone first argument
+    /// being the strategy name.
+    pub fn parse(
+        args: impl IntoIterator<Item = String>,
+        get_var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, String> {
+        let mut config = BotConfig::default();
+        for (option, var) in OPTIONS {
+            if let Some(value) = get_var(var) {
+                config.set(option, &value)?;
+            }
+        }
+        let mut args = args.into_iter().peekable();
+        if let Some(first) = args.next_if(|arg| !arg.starts_with("--")) {
+            config.set("--strategy", &first)?;
+        }
+        while let Some(option) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for {}", option))?;
+            config.set(&option, &value)?;
+        }
+        Ok(config)
+    }
+
+    fn set(&mut self, option: &str, value: &str) -> Result<(), String> {
+        match option {
+            "--strategy" => self.strategy = parse_strategy(value)?,
+            "--opponent" => self.opponent = parse_strategy(value)?,
+            "--time-ms" => {
+                self.turn_budget = Duration::from_millis(parse_number(option, value)?);
+            }
+            "--seed" => self.seed = parse_number(option, value)?,
+            "--weights" => {
+                // a path to a weight file, else the weights themselves
+                let text = fs::read_to_string(value).unwrap_or_else(|_| value.to_string());
+                self.evaluation = EvaluationParams::parse(&text)?;
+            }
+            "--self-play" => self.self_play_games = parse_number(option, value)? as usize,
+            "--output" => self.output = value.to_string(),
+            "--checks" => self.checks_per_turn = parse_number(option, value)? as u32,
+            "--log-level" => self.log_level = value.parse()?,
+            _ => return Err(format!("Unknown option {}", option)),
+        }
+        Ok(())
+    }
+
+    pub fn is_logged(&self, level: LogLevel) -> bool {
+        level <= self.log_level
+    }
+
+    pub fn get_self_play_config(&self) -> SelfPlayConfig {
+        SelfPlayConfig {
+            games: self.self_play_games,
+            strategies: [self.opponent.clone(), self.strategy.clone()],
+            evaluation: self.evaluation,
+            seed: self.seed,
+            checks_per_turn: self.checks_per_turn,
+        }
+    }
+}
+
+fn parse_strategy(name: &str) -> Result<String, String> {
+    if registry::STRATEGY_NAMES.contains(&name) {
+        Ok(name.to_string())
+    } else {
+        Err(format!("Unknown strategy {}", name))
+    }
+}
+
+fn parse_number(option: &str, value: &str) -> Result<u64, String> {
+    value
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid value for {}: {}", option, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::strategies::{greedy, mcts};
+
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_defaults_without_options() {
+        let config = BotConfig::parse(Vec::new(), |_| None).unwrap();
+
+        assert_eq!(config, BotConfig::default());
+        assert!(config.is_logged(LogLevel::Info));
+        assert!(!config.is_logged(LogLevel::Debug));
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        let config = BotConfig::parse(
+            args("mcts --time-ms 80 --seed 7 --weights income=4 --log-level DEBUG"),
+            |_| None,
+        )
+        .unwrap();
+
+        assert_eq!(config.strategy, mcts::NAME);
+        assert_eq!(config.turn_budget, Duration::from_millis(80));
+        assert_eq!(config.seed, 7);
+        assert_eq!(config.evaluation.income, 4.0);
+        assert_eq!(config.log_level, LogLevel::Debug);
+    }
+
+    #[test]
+    fn test_arguments_override_the_environment() {
+        let config = BotConfig::parse(args("--seed 3"), |name| match name {
+            "BOT_SEED" => Some("1".to_string()),
+            "BOT_STRATEGY" => Some(greedy::NAME.to_string()),
+            _ => None,
+        })
+        .unwrap();
+
+        assert_eq!(config.seed, 3);
+        assert_eq!(config.strategy, greedy::NAME);
+    }
+
+    #[test]
+    fn test_weight_file() {
+        let path = std::env::temp_dir().join("bot_config_weights.txt");
+        fs::write(&path, "organs=2\nterritory=0.1\n").unwrap();
+        let config = BotConfig::parse(
+            vec!["--weights".to_string(), path.to_string_lossy().to_string()],
+            |_| None,
+        )
+        .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.evaluation.organs, 2.0);
+        assert_eq!(config.evaluation.territory, 0.1);
+    }
+
+    #[test]
+    fn test_self_play_options() {
+        let config = BotConfig::parse(
+            args("--self-play 5 --opponent greedy --output games.jsonl --checks 20 --seed 4"),
+            |_| None,
+        )
+        .unwrap();
+        let self_play = config.get_self_play_config();
+
+        assert_eq!(self_play.games, 5);
+        assert_eq!(
+            self_play.strategies,
+            [
+                greedy::NAME.to_string(),
+                registry::DEFAULT_STRATEGY.to_string()
+            ]
+        );
+        assert_eq!(self_play.seed, 4);
+        assert_eq!(self_play.checks_per_turn, 20);
+        assert_eq!(config.output, "games.jsonl");
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        assert!(BotConfig::parse(args("unknown"), |_| None).is_err());
+        assert!(BotConfig::parse(args("--seed"), |_| None).is_err());
+        assert!(BotConfig::parse(args("--seed x"), |_| None).is_err());
+        assert!(BotConfig::parse(args("--speed 2"), |_| None).is_err());
+        assert!(BotConfig::parse(args("--log-level loud"), |_| None).is_err());
+        assert!(BotConfig::parse(args("--opponent unknown"), |_| None).is_err());
+    }
+}
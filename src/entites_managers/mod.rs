@@ -1 +1,3 @@
 pub mod organs_manager;
+pub mod proteins_manager;
+pub mod threat_map;
@@ -1 +1,256 @@
+use std::collections::HashMap;
 
+use crate::game_entities::{
+    cell,
+    coord::{self, Coord},
+    grid::Grid,
+    organ::{self, Organ},
+    organ_direction::OrganDirection,
+    organ_type::OrganType,
+    player::Player,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrganNode {
+    pub organ: Organ,
+    pub parent: Option<Coord>,
+    pub root: Coord,
+    pub depth: usize,
+    pub subtree_size: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExposedOrgan {
+    pub coord: Coord,
+    pub subtree_size: usize,
+}
+
+pub struct OrgansManager {
+    nodes: HashMap<Coord, OrganNode>,
+}
+
+impl OrgansManager {
+    pub fn new(grid: &Grid) -> Self {
+        let mut manager = OrgansManager {
+            nodes: HashMap::new(),
+        };
+        for x in 0..grid.width {
+            for y in 0..grid.height {
+                let cell = grid.get_cell(x, y);
+                if cell::is_organ(cell) && organ::is_root(cell::get_organ(cell).unwrap()) {
+                    manager.add_tree(grid, coord::new(x, y));
+                }
+            }
+        }
+        manager
+    }
+
+    fn add_tree(&mut self, grid: &Grid, root: Coord) {
+        let mut order = Vec::new();
+        let mut stack = vec![(root, None, 0)];
+        while let Some((current, parent, depth)) = stack.pop() {
+            let organ = cell::get_organ(grid.get_cell_from_coord(current)).unwrap();
+            self.nodes.insert(
+                current,
+                OrganNode {
+                    organ,
+                    parent,
+                    root,
+                    depth,
+                    subtree_size: 1,
+                },
+            );
+            order.push(current);
+            if let Some(children) = grid.get_children(current) {
+                for child in children {
+                    stack.push((*child, Some(current), depth + 1));
+                }
+            }
+        }
+        for current in order.into_iter().rev() {
+            let node = self.nodes[&current];
+            if let Some(parent) = node.parent {
+                self.nodes.get_mut(&parent).unwrap().subtree_size += node.subtree_size;
+            }
+        }
+    }
+
+    pub fn get_node(&self, coord: Coord) -> Option<&OrganNode> {
+        self.nodes.get(&coord)
+    }
+
+    pub fn get_subtree_size(&self, coord: Coord) -> usize {
+        self.nodes.get(&coord).map_or(0, |node| node.subtree_size)
+    }
+
+    pub fn get_depth(&self, coord: Coord) -> Option<usize> {
+        self.nodes.get(&coord).map(|node| node.depth)
+    }
+
+    /// Coords from `coord` (included) up to its root (included).
+    pub fn get_path_to_root(&self, coord: Coord) -> Vec<Coord> {
+        let mut path = Vec::new();
+        let mut current = Some(coord);
+        while let Some(c) = current {
+            match self.nodes.get(&c) {
+                Some(node) => {
+                    path.push(c);
+                    current = node.parent;
+                }
+                None => break,
+            }
+        }
+        path
+    }
+
+    /// An organ is exposed when the opponent can grow a tentacle facing it next turn.
+    pub fn is_exposed(&self, grid: &Grid, coord: Coord, opponent: &Player) -> bool {
+        if !opponent.can_buy(OrganType::Tentacle) {
+            return false;
+        }
+        let tentacle = organ::new(
+            opponent.get_id(),
+            OrganType::Tentacle,
+            OrganDirection::North,
+            0,
+        );
+        grid.get_adjacent_coords(coord)
+            .into_iter()
+            .any(|attack_coord| grid.can_add_organ_without_root_coord(attack_coord, tentacle))
+    }
+
+    /// Exposed organs of `owner`, the ones whose loss removes the most organs first.
+    pub fn get_exposed_organs(
+        &self,
+        grid: &Grid,
+        owner: u8,
+        opponent: &Player,
+    ) -> Vec<ExposedOrgan> {
+        let mut exposed: Vec<ExposedOrgan> = self
+            .nodes
+            .iter()
+            .filter(|(_, node)| organ::get_owner(node.organ) == owner)
+            .filter(|(&coord, _)| self.is_exposed(grid, coord, opponent))
+            .map(|(&coord, node)| ExposedOrgan {
+                coord,
+                subtree_size: node.subtree_size,
+            })
+            .collect();
+        exposed.sort_by(|a, b| {
+            b.subtree_size
+                .cmp(&a.subtree_size)
+                .then(a.coord.cmp(&b.coord))
+        });
+        exposed
+    }
+
+    /// Exposed organs that carry other organs: killing one of them cuts its whole subtree.
+    pub fn get_exposed_articulation_points(
+        &self,
+        grid: &Grid,
+        owner: u8,
+        opponent: &Player,
+    ) -> Vec<ExposedOrgan> {
+        self.get_exposed_organs(grid, owner, opponent)
+            .into_iter()
+            .filter(|exposed| exposed.subtree_size > 1)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::protein::Protein;
+
+    use super::*;
+
+    fn build_grid() -> Grid {
+        let mut grid = Grid::new(6, 4);
+        let root = coord::new(0, 0);
+        grid.add_organ(
+            root,
+            organ::new(0, OrganType::Root, OrganDirection::North, root),
+        );
+        let basic = organ::new(0, OrganType::Basic, OrganDirection::North, root);
+        grid.add_organ(coord::new(1, 0), basic);
+        grid.add_organ(coord::new(2, 0), basic);
+        grid.add_organ(coord::new(3, 0), basic);
+        grid.add_organ(coord::new(2, 1), basic);
+        grid.add_organ(coord::new(0, 1), basic);
+        grid
+    }
+
+    #[test]
+    fn test_subtree_sizes_and_depths() {
+        let grid = build_grid();
+        let manager = OrgansManager::new(&grid);
+
+        assert_eq!(manager.get_subtree_size(coord::new(0, 0)), 6);
+        assert_eq!(manager.get_subtree_size(coord::new(1, 0)), 4);
+        assert_eq!(manager.get_subtree_size(coord::new(2, 0)), 3);
+        assert_eq!(manager.get_subtree_size(coord::new(3, 0)), 1);
+        assert_eq!(manager.get_subtree_size(coord::new(5, 3)), 0);
+        assert_eq!(manager.get_depth(coord::new(0, 0)), Some(0));
+        assert_eq!(manager.get_depth(coord::new(2, 1)), Some(3));
+        assert_eq!(manager.get_depth(coord::new(5, 3)), None);
+    }
+
+    #[test]
+    fn test_get_path_to_root() {
+        let grid = build_grid();
+        let manager = OrgansManager::new(&grid);
+
+        assert_eq!(
+            manager.get_path_to_root(coord::new(2, 1)),
+            vec![
+                coord::new(2, 1),
+                coord::new(2, 0),
+                coord::new(1, 0),
+                coord::new(0, 0)
+            ]
+        );
+        assert!(manager.get_path_to_root(coord::new(5, 3)).is_empty());
+    }
+
+    #[test]
+    fn test_get_exposed_articulation_points() {
+        let mut grid = build_grid();
+        let opp_root = coord::new(1, 2);
+        grid.add_organ(
+            opp_root,
+            organ::new(1, OrganType::Root, OrganDirection::North, opp_root),
+        );
+        let manager = OrgansManager::new(&grid);
+        let mut opponent = Player::new(1);
+
+        assert!(manager.get_exposed_organs(&grid, 0, &opponent).is_empty());
+
+        for protein in [Protein::A, Protein::B, Protein::C, Protein::D] {
+            opponent.add_protein(protein, 1);
+        }
+        assert_eq!(
+            manager.get_exposed_organs(&grid, 0, &opponent),
+            vec![
+                ExposedOrgan {
+                    coord: coord::new(1, 0),
+                    subtree_size: 4
+                },
+                ExposedOrgan {
+                    coord: coord::new(0, 1),
+                    subtree_size: 1
+                },
+                ExposedOrgan {
+                    coord: coord::new(2, 1),
+                    subtree_size: 1
+                },
+            ]
+        );
+        assert_eq!(
+            manager.get_exposed_articulation_points(&grid, 0, &opponent),
+            vec![ExposedOrgan {
+                coord: coord::new(1, 0),
+                subtree_size: 4
+            }]
+        );
+    }
+}
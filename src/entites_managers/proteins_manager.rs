@@ -0,0 +1,267 @@
+use std::collections::VecDeque;
+
+use crate::game_entities::{
+    cell,
+    coord::{self, Coord},
+    grid::{Grid, MAX_HEIGHT, MAX_WIDTH},
+    organ, organ_direction,
+    organ_type::OrganType,
+    protein::{Protein, PROTEINS},
+    protein_wallet,
+};
+
+pub const ABSORB_GAIN: u32 = 3;
+
+pub const UNREACHABLE: u8 = u8::MAX;
+
+const NB_PLAYERS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProteinSourceReport {
+    pub coord: Coord,
+    pub protein: Protein,
+    /// Number of growths each player needs to put an organ on the source.
+    pub distances: [u8; NB_PLAYERS],
+    /// Number of growths each player needs to put a harvester next to the source.
+    pub harvest_distances: [u8; NB_PLAYERS],
+    /// None when nobody reaches the source or both players reach it together.
+    pub first_reached_by: Option<u8>,
+    pub harvested_by: [bool; NB_PLAYERS],
+}
+
+impl ProteinSourceReport {
+    pub fn is_harvested(&self) -> bool {
+        self.harvested_by.iter().any(|&harvested| harvested)
+    }
+
+    pub fn get_harvest_income(&self, player_id: u8, remaining_turns: u32) -> u32 {
+        let harvest_distance = self.harvest_distances[player_id as usize];
+        if harvest_distance == UNREACHABLE {
+            return 0;
+        }
+        remaining_turns.saturating_sub(harvest_distance as u32)
+    }
+
+    /// Whether building a harvester next to the source beats absorbing it once the
+    /// harvester is paid for, an already harvested source is never worth absorbing for its owner.
+    pub fn should_harvest(&self, player_id: u8, remaining_turns: u32) -> bool {
+        self.harvested_by[player_id as usize]
+            || self.get_harvest_income(player_id, remaining_turns)
+                > ABSORB_GAIN + get_harvester_cost()
+    }
+}
+
+/// Proteins spent on a harvester, of all types.
+fn get_harvester_cost() -> u32 {
+    let cost = OrganType::Harvester.get_cost();
+    PROTEINS
+        .iter()
+        .map(|&protein| protein_wallet::get(cost, protein) as u32)
+        .sum()
+}
+
+pub struct ProteinsManager {
+    distances: [[u8; MAX_WIDTH * MAX_HEIGHT]; NB_PLAYERS],
+    reports: Vec<ProteinSourceReport>,
+}
+
+impl ProteinsManager {
+    pub fn new(grid: &Grid) -> Self {
+        let mut manager = ProteinsManager {
+            distances: [
+                Self::compute_distances(grid, 0),
+                Self::compute_distances(grid, 1),
+            ],
+            reports: Vec::new(),
+        };
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                if let Some(protein) = cell::get_protein(grid.get_cell(x, y)) {
+                    let report = manager.build_report(grid, coord::new(x, y), protein);
+                    manager.reports.push(report);
+                }
+            }
+        }
+        manager
+    }
+
+    fn index(grid: &Grid, coord: Coord) -> usize {
+        coord::x(coord) as usize + grid.width as usize * coord::y(coord) as usize
+    }
+
+    fn is_growable(grid: &Grid, coord: Coord, owner: u8) -> bool {
+        cell::is_growable(grid.get_cell_from_coord(coord))
+            && !grid.is_canceled_by_tentacle(coord::x(coord), coord::y(coord), owner)
+    }
+
+    fn compute_distances(grid: &Grid, owner: u8) -> [u8; MAX_WIDTH * MAX_HEIGHT] {
+        let mut distances = [UNREACHABLE; MAX_WIDTH * MAX_HEIGHT];
+        let mut queue = VecDeque::new();
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                if cell::is_owned_by(grid.get_cell(x, y), owner) {
+                    let coord = coord::new(x, y);
+                    distances[Self::index(grid, coord)] = 0;
+                    queue.push_back(coord);
+                }
+            }
+        }
+        while let Some(current) = queue.pop_front() {
+            let distance = distances[Self::index(grid, current)];
+            for next in grid.get_adjacent_coords(current) {
+                let index = Self::index(grid, next);
+                if distances[index] == UNREACHABLE && Self::is_growable(grid, next, owner) {
+                    distances[index] = distance + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+
+    fn build_report(&self, grid: &Grid, coord: Coord, protein: Protein) -> ProteinSourceReport {
+        let index = Self::index(grid, coord);
+        let distances = [self.distances[0][index], self.distances[1][index]];
+        let mut harvest_distances = [UNREACHABLE; NB_PLAYERS];
+        let mut harvested_by = [false; NB_PLAYERS];
+        for neighbour in grid.get_adjacent_coords(coord) {
+            let cell = grid.get_cell_from_coord(neighbour);
+            if cell::is_organ(cell) {
+                let organ = cell::get_organ(cell).unwrap();
+                if organ::is_harvester(organ)
+                    && organ_direction::found_next_direction(neighbour, coord)
+                        == organ::get_direction(organ)
+                {
+                    harvested_by[organ::get_owner(organ) as usize] = true;
+                }
+                continue;
+            }
+            let neighbour_index = Self::index(grid, neighbour);
+            for (player, harvest_distance) in harvest_distances.iter_mut().enumerate() {
+                *harvest_distance =
+                    (*harvest_distance).min(self.distances[player][neighbour_index]);
+            }
+        }
+        let first_reached_by = match distances[0].cmp(&distances[1]) {
+            std::cmp::Ordering::Less => Some(0),
+            std::cmp::Ordering::Greater => Some(1),
+            std::cmp::Ordering::Equal => None,
+        };
+        ProteinSourceReport {
+            coord,
+            protein,
+            distances,
+            harvest_distances,
+            first_reached_by,
+            harvested_by,
+        }
+    }
+
+    pub fn get_reports(&self) -> &Vec<ProteinSourceReport> {
+        &self.reports
+    }
+
+    pub fn get_report(&self, coord: Coord) -> Option<&ProteinSourceReport> {
+        self.reports.iter().find(|report| report.coord == coord)
+    }
+
+    /// Number of growths `owner` needs to reach `coord`, `UNREACHABLE` if it cannot.
+    pub fn get_distance(&self, grid: &Grid, owner: u8, coord: Coord) -> u8 {
+        self.distances[owner as usize][Self::index(grid, coord)]
+    }
+
+    /// Sources `owner` reaches strictly before its opponent and that nobody harvests yet.
+    pub fn get_contested_sources_won_by(&self, owner: u8) -> Vec<&ProteinSourceReport> {
+        self.reports
+            .iter()
+            .filter(|report| report.first_reached_by == Some(owner) && !report.is_harvested())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::{organ_direction::OrganDirection, organ_type::OrganType};
+
+    use super::*;
+
+    fn build_grid() -> Grid {
+        let mut grid = Grid::new(7, 3);
+        let root0 = coord::new(0, 1);
+        let root1 = coord::new(6, 1);
+        grid.add_organ(
+            root0,
+            organ::new(0, OrganType::Root, OrganDirection::North, root0),
+        );
+        grid.add_organ(
+            root1,
+            organ::new(1, OrganType::Root, OrganDirection::North, root1),
+        );
+        grid.set_cell(2, 1, cell::new(false, Some(Protein::A), None));
+        grid.set_cell(3, 0, cell::new(false, Some(Protein::B), None));
+        grid.set_cell(5, 2, cell::new(false, Some(Protein::C), None));
+        grid.set_cell(3, 2, cell::new(true, None, None));
+        grid
+    }
+
+    #[test]
+    fn test_first_reached_by() {
+        let grid = build_grid();
+        let manager = ProteinsManager::new(&grid);
+
+        let report_a = manager.get_report(coord::new(2, 1)).unwrap();
+        assert_eq!(report_a.protein, Protein::A);
+        assert_eq!(report_a.distances, [2, 4]);
+        assert_eq!(report_a.first_reached_by, Some(0));
+
+        let report_b = manager.get_report(coord::new(3, 0)).unwrap();
+        assert_eq!(report_b.distances, [4, 4]);
+        assert_eq!(report_b.first_reached_by, None);
+
+        let report_c = manager.get_report(coord::new(5, 2)).unwrap();
+        assert_eq!(report_c.distances, [6, 2]);
+        assert_eq!(report_c.harvest_distances, [5, 1]);
+        assert_eq!(report_c.first_reached_by, Some(1));
+        assert_eq!(manager.get_contested_sources_won_by(0).len(), 1);
+    }
+
+    #[test]
+    fn test_harvested_by() {
+        let mut grid = build_grid();
+        grid.add_organ(
+            coord::new(1, 1),
+            organ::new(
+                0,
+                OrganType::Harvester,
+                OrganDirection::East,
+                coord::new(0, 1),
+            ),
+        );
+        let manager = ProteinsManager::new(&grid);
+        let report = manager.get_report(coord::new(2, 1)).unwrap();
+
+        assert_eq!(report.harvested_by, [true, false]);
+        assert!(report.is_harvested());
+        assert!(report.should_harvest(0, 0));
+        let won = manager.get_contested_sources_won_by(0);
+        assert_eq!(won.len(), 1);
+        assert_eq!(won[0].coord, coord::new(3, 0));
+    }
+
+    #[test]
+    fn test_should_harvest_depends_on_remaining_turns() {
+        let grid = build_grid();
+        let manager = ProteinsManager::new(&grid);
+        let report = manager.get_report(coord::new(2, 1)).unwrap();
+
+        assert_eq!(report.harvest_distances[0], 1);
+        assert_eq!(report.get_harvest_income(0, 3), 2);
+        assert!(!report.should_harvest(0, 3));
+        // the income must also pay for the harvester
+        assert_eq!(report.get_harvest_income(0, 6), ABSORB_GAIN + 2);
+        assert!(!report.should_harvest(0, 6));
+        assert!(report.should_harvest(0, 7));
+        assert!(report.should_harvest(0, 20));
+        assert_eq!(report.get_harvest_income(1, 20), 17);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::game_entities::{
+    cell,
+    coord::{self, Coord},
+    grid::{Grid, MAX_HEIGHT, MAX_WIDTH},
+    organ,
+    organ_direction::{self, OrganDirection},
+    organ_type::OrganType,
+    player::Player,
+};
+
+pub type Threat = u8;
+
+const NONE: Threat = 0b000;
+const BLOCKED: Threat = 0b001;
+const ATTACKABLE: Threat = 0b010;
+const REACHABLE: Threat = 0b100;
+
+const GROWABLE_TYPES: [OrganType; 4] = [
+    OrganType::Basic,
+    OrganType::Harvester,
+    OrganType::Sporer,
+    OrganType::Tentacle,
+];
+
+/// What the opponent can do to each cell next turn.
+/// - blocked: an existing enemy tentacle faces the cell
+/// - attackable: the enemy can grow a tentacle facing the cell
+/// - reachable: the enemy can grow an organ on the cell
+pub struct ThreatMap {
+    threats: [Threat; MAX_WIDTH * MAX_HEIGHT],
+    width: u8,
+    height: u8,
+}
+
+impl ThreatMap {
+    pub fn new(grid: &Grid, opponent: &Player) -> Self {
+        let mut threat_map = ThreatMap {
+            threats: [NONE; MAX_WIDTH * MAX_HEIGHT],
+            width: grid.width,
+            height: grid.height,
+        };
+        let can_grow = GROWABLE_TYPES
+            .iter()
+            .any(|&organ_type| opponent.can_buy(organ_type));
+        let can_grow_tentacle = opponent.can_buy(OrganType::Tentacle);
+        let opponent_organ = organ::new(opponent.get_id(), OrganType::Basic, OrganDirection::X, 0);
+        for x in 0..grid.width {
+            for y in 0..grid.height {
+                let coord = coord::new(x, y);
+                let cell = grid.get_cell(x, y);
+                if cell::is_tentacle(cell) && cell::is_owned_by(cell, opponent.get_id()) {
+                    threat_map.add_faced_by_tentacle(grid, coord, cell);
+                }
+                if can_grow && grid.can_add_organ_without_root_coord(coord, opponent_organ) {
+                    threat_map.add(coord, REACHABLE);
+                    if can_grow_tentacle {
+                        for target in grid.get_adjacent_coords(coord) {
+                            threat_map.add(target, ATTACKABLE);
+                        }
+                    }
+                }
+            }
+        }
+        threat_map
+    }
+
+    fn add_faced_by_tentacle(&mut self, grid: &Grid, tentacle_coord: Coord, cell: cell::Cell) {
+        let direction = organ::get_direction(cell::get_organ(cell).unwrap());
+        for target in grid.get_adjacent_coords(tentacle_coord) {
+            if organ_direction::found_next_direction(tentacle_coord, target) == direction {
+                self.add(target, BLOCKED);
+            }
+        }
+    }
+
+    fn index(&self, coord: Coord) -> usize {
+        coord::x(coord) as usize + self.width as usize * coord::y(coord) as usize
+    }
+
+    fn add(&mut self, coord: Coord, threat: Threat) {
+        let index = self.index(coord);
+        self.threats[index] |= threat;
+    }
+
+    fn get(&self, coord: Coord) -> Threat {
+        self.threats[self.index(coord)]
+    }
+
+    pub fn is_blocked(&self, coord: Coord) -> bool {
+        self.get(coord) & BLOCKED != NONE
+    }
+
+    pub fn is_attackable(&self, coord: Coord) -> bool {
+        self.get(coord) & ATTACKABLE != NONE
+    }
+
+    pub fn is_reachable(&self, coord: Coord) -> bool {
+        self.get(coord) & REACHABLE != NONE
+    }
+
+    /// True when an organ placed on the cell can be prevented or killed next turn.
+    pub fn is_threatened(&self, coord: Coord) -> bool {
+        self.get(coord) & (BLOCKED | ATTACKABLE) != NONE
+    }
+
+    pub fn get_threatened_coords(&self) -> Vec<Coord> {
+        let mut coords = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.is_threatened(coord::new(x, y)) {
+                    coords.push(coord::new(x, y));
+                }
+            }
+        }
+        coords
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::protein::Protein;
+
+    use super::*;
+
+    fn rich_player(id: u8) -> Player {
+        let mut player = Player::new(id);
+        for protein in [Protein::A, Protein::B, Protein::C, Protein::D] {
+            player.add_protein(protein, 5);
+        }
+        player
+    }
+
+    #[test]
+    fn test_existing_tentacle_blocks_faced_cell() {
+        let mut grid = Grid::new(4, 4);
+        let root = coord::new(0, 0);
+        grid.add_organ(
+            root,
+            organ::new(1, OrganType::Root, OrganDirection::North, root),
+        );
+        grid.add_organ(
+            coord::new(0, 1),
+            organ::new(1, OrganType::Tentacle, OrganDirection::East, root),
+        );
+        let threat_map = ThreatMap::new(&grid, &Player::new(1));
+
+        assert!(threat_map.is_blocked(coord::new(1, 1)));
+        assert!(!threat_map.is_blocked(coord::new(0, 2)));
+        assert!(!threat_map.is_attackable(coord::new(1, 1)));
+        assert_eq!(threat_map.get_threatened_coords(), vec![coord::new(1, 1)]);
+    }
+
+    #[test]
+    fn test_potential_tentacles_from_frontier() {
+        let mut grid = Grid::new(4, 4);
+        let root = coord::new(0, 0);
+        grid.add_organ(
+            root,
+            organ::new(1, OrganType::Root, OrganDirection::North, root),
+        );
+        let threat_map = ThreatMap::new(&grid, &rich_player(1));
+
+        assert!(threat_map.is_reachable(coord::new(1, 0)));
+        assert!(threat_map.is_reachable(coord::new(0, 1)));
+        assert!(!threat_map.is_reachable(coord::new(1, 1)));
+        assert!(threat_map.is_attackable(coord::new(1, 1)));
+        assert!(threat_map.is_attackable(coord::new(2, 0)));
+        assert!(threat_map.is_attackable(coord::new(0, 0)));
+        assert!(!threat_map.is_attackable(coord::new(2, 2)));
+        assert!(!threat_map.is_threatened(coord::new(3, 3)));
+    }
+
+    #[test]
+    fn test_poor_opponent_threatens_nothing_new() {
+        let mut grid = Grid::new(4, 4);
+        let root = coord::new(0, 0);
+        grid.add_organ(
+            root,
+            organ::new(1, OrganType::Root, OrganDirection::North, root),
+        );
+        let threat_map = ThreatMap::new(&grid, &Player::new(1));
+
+        assert!(!threat_map.is_reachable(coord::new(1, 0)));
+        assert!(threat_map.get_threatened_coords().is_empty());
+    }
+
+    #[test]
+    fn test_own_tentacle_protects_cell() {
+        let mut grid = Grid::new(4, 4);
+        let opp_root = coord::new(0, 0);
+        let own_root = coord::new(3, 0);
+        grid.add_organ(
+            opp_root,
+            organ::new(1, OrganType::Root, OrganDirection::North, opp_root),
+        );
+        grid.add_organ(
+            own_root,
+            organ::new(0, OrganType::Root, OrganDirection::North, own_root),
+        );
+        grid.add_organ(
+            coord::new(2, 0),
+            organ::new(0, OrganType::Tentacle, OrganDirection::West, own_root),
+        );
+        let threat_map = ThreatMap::new(&grid, &rich_player(1));
+
+        assert!(!threat_map.is_reachable(coord::new(1, 0)));
+        assert!(threat_map.is_reachable(coord::new(0, 1)));
+    }
+}
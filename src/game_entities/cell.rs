@@ -4,7 +4,7 @@ use super::{
     protein::Protein,
 };
 
-pub type Cell = u16;
+pub type Cell = u32;
 
 const EMPTY: Cell = 0b00;
 const OBSTACLE: Cell = 0b01;
@@ -17,8 +17,8 @@ pub fn new(obstacle: bool, protein: Option<Protein>, organ: Option<Organ>) -> Ce
     match (obstacle, protein, organ) {
         (true, _, _) => OBSTACLE,
         (false, None, None) => EMPTY,
-        (false, Some(protein), None) => (protein as u16) << 2 | PROTEIN,
-        (false, None, Some(organ)) => ((organ as u16) << 2) | ORGAN,
+        (false, Some(protein), None) => (protein as Cell) << 2 | PROTEIN,
+        (false, None, Some(organ)) => (organ << 2) | ORGAN,
         _ => panic!("Invalid cell"),
     }
 }
@@ -37,14 +37,14 @@ pub fn get_protein(cell: Cell) -> Option<Protein> {
 
 pub fn get_organ(cell: Cell) -> Option<Organ> {
     if contains_organ(cell) {
-        Some((cell >> 2) as Organ)
+        Some(cell >> 2)
     } else {
         panic!("\x1b[31mCell {:?} does not contain an organ\x1b[0m", cell);
     }
 }
 
 pub fn set_protein(cell: &mut Cell, protein: Protein) {
-    *cell = (protein as u16) << 2 | PROTEIN;
+    *cell = (protein as Cell) << 2 | PROTEIN;
 }
 
 pub fn set_obstacle(cell: &mut Cell) {
@@ -55,7 +55,7 @@ pub fn unset_obstacle(cell: &mut Cell) {
     *cell = EMPTY;
 }
 
-pub fn place_organ(cell: &mut Cell, organ_id: u16) {
+pub fn place_organ(cell: &mut Cell, organ_id: Organ) {
     *cell = organ_id << 2 | ORGAN;
 }
 
@@ -71,6 +71,11 @@ pub fn is_protein(cell: Cell) -> bool {
     get_type_cell(cell) == PROTEIN
 }
 
+/// Organs can grow on empty cells and on protein sources, absorbing them.
+pub fn is_growable(cell: Cell) -> bool {
+    is_empty(cell) || is_protein(cell)
+}
+
 pub fn is_organ(cell: Cell) -> bool {
     get_type_cell(cell) == ORGAN
 }
@@ -88,9 +93,7 @@ pub fn is_owned_and_rooted_by(cell: Cell, owner: u8, root_coord: Coord) -> bool
 }
 
 pub fn has_root_coord(cell1: Cell, root_coord: Coord) -> bool {
-    is_organ(cell1)
-        && organ::get_root_coord(get_organ(cell1).unwrap())
-            == root_coord
+    is_organ(cell1) && organ::get_root_coord(get_organ(cell1).unwrap()) == root_coord
 }
 
 pub fn contains_organ(cell: Cell) -> bool {
@@ -111,34 +114,54 @@ pub fn clear(cell: &mut Cell) {
 
 #[cfg(test)]
 mod tests {
-    use crate::game_entities::cell;
+    use crate::game_entities::{
+        cell, coord, organ_direction::OrganDirection, organ_type::OrganType,
+    };
 
     use super::*;
 
     #[test]
     fn test_cell() {
         let cell = cell::new(false, Some(Protein::A), None);
-        assert_eq!(cell::is_obstacle(cell), false);
+        assert!(!cell::is_obstacle(cell));
         assert_eq!(cell::get_protein(cell), Some(Protein::A));
-        assert_eq!(cell::contains_organ(cell), false);
-        assert_eq!(cell::contains_protein(cell), true);
+        assert!(!cell::contains_organ(cell));
+        assert!(cell::contains_protein(cell));
     }
 
     #[test]
     fn test_cell_wall() {
         let cell = cell::new(true, None, None);
-        assert_eq!(cell::is_obstacle(cell), true);
+        assert!(cell::is_obstacle(cell));
         assert_eq!(cell::get_protein(cell), None);
-        assert_eq!(cell::contains_organ(cell), false);
-        assert_eq!(cell::contains_protein(cell), false);
+        assert!(!cell::contains_organ(cell));
+        assert!(!cell::contains_protein(cell));
+    }
+
+    #[test]
+    fn test_cell_organ_keeps_root_coord() {
+        let root_coord = coord::new(23, 11);
+        let organ = organ::new(1, OrganType::Tentacle, OrganDirection::East, root_coord);
+        let cell = cell::new(false, None, Some(organ));
+        assert_eq!(cell::get_organ(cell), Some(organ));
+        assert!(cell::has_root_coord(cell, root_coord));
+        assert!(cell::is_owned_and_rooted_by(cell, 1, root_coord));
+    }
+
+    #[test]
+    fn test_cell_is_growable() {
+        assert!(cell::is_growable(cell::new(false, None, None)));
+        assert!(cell::is_growable(cell::new(false, Some(Protein::D), None)));
+        assert!(!cell::is_growable(cell::new(true, None, None)));
+        assert!(!cell::is_growable(cell::new(false, None, Some(1))));
     }
 
     #[test]
     fn test_cell_organ() {
         let cell = cell::new(false, None, Some(1));
-        assert_eq!(cell::is_obstacle(cell), false);
+        assert!(!cell::is_obstacle(cell));
         assert_eq!(cell::get_protein(cell), None);
-        assert_eq!(cell::contains_organ(cell), true);
-        assert_eq!(cell::contains_protein(cell), false);
+        assert!(cell::contains_organ(cell));
+        assert!(!cell::contains_protein(cell));
     }
 }
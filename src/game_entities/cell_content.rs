@@ -0,0 +1,100 @@
+use super::{
+    cell::{self, Cell},
+    coord::Coord,
+    organ::{self, Organ},
+    organ_direction::OrganDirection,
+    organ_type::OrganType,
+    protein::Protein,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrganEntity {
+    pub coord: Coord,
+    pub organ: Organ,
+    pub owner: u8,
+    pub organ_type: OrganType,
+    pub direction: OrganDirection,
+    pub root_coord: Coord,
+}
+
+impl OrganEntity {
+    pub fn new(coord: Coord, organ: Organ) -> Self {
+        OrganEntity {
+            coord,
+            organ,
+            owner: organ::get_owner(organ),
+            organ_type: organ::get_type(organ),
+            direction: organ::get_direction(organ),
+            root_coord: organ::get_root_coord(organ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProteinSource {
+    pub coord: Coord,
+    pub protein: Protein,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellContent {
+    Empty,
+    Wall,
+    Protein(ProteinSource),
+    Organ(OrganEntity),
+}
+
+impl CellContent {
+    pub fn from_cell(coord: Coord, cell: Cell) -> CellContent {
+        if cell::is_obstacle(cell) {
+            CellContent::Wall
+        } else if let Some(protein) = cell::get_protein(cell) {
+            CellContent::Protein(ProteinSource { coord, protein })
+        } else if cell::is_organ(cell) {
+            CellContent::Organ(OrganEntity::new(coord, cell::get_organ(cell).unwrap()))
+        } else {
+            CellContent::Empty
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::coord;
+
+    use super::*;
+
+    #[test]
+    fn test_from_cell() {
+        let coord = coord::new(5, 2);
+        let root_coord = coord::new(7, 3);
+        let organ = organ::new(1, OrganType::Sporer, OrganDirection::West, root_coord);
+
+        assert_eq!(
+            CellContent::from_cell(coord, cell::new(false, None, None)),
+            CellContent::Empty
+        );
+        assert_eq!(
+            CellContent::from_cell(coord, cell::new_wall()),
+            CellContent::Wall
+        );
+        assert_eq!(
+            CellContent::from_cell(coord, cell::new(false, Some(Protein::C), None)),
+            CellContent::Protein(ProteinSource {
+                coord,
+                protein: Protein::C
+            })
+        );
+        assert_eq!(
+            CellContent::from_cell(coord, cell::new(false, None, Some(organ))),
+            CellContent::Organ(OrganEntity {
+                coord,
+                organ,
+                owner: 1,
+                organ_type: OrganType::Sporer,
+                direction: OrganDirection::West,
+                root_coord,
+            })
+        );
+    }
+}
@@ -93,7 +93,7 @@ mod tests {
     #[test]
     fn test_clone() {
         let pos1 = new(1, 1);
-        let pos2 = pos1.clone();
+        let pos2 = pos1;
         assert_eq!(pos1, pos2);
     }
 }
@@ -0,0 +1,397 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+use super::{
+    cell,
+    cell_content::CellContent,
+    coord::{self, Coord},
+    grid::Grid,
+    organ::{self, Organ},
+    organ_direction::OrganDirection,
+    organ_owner::OrganeOwner,
+    organ_type::OrganType,
+    player::Player,
+    protein::Protein,
+    protein_wallet::{self, ProteinWallet},
+};
+
+pub const MY_ID: u8 = OrganeOwner::PlayerOwner as u8;
+pub const OPPONENT_ID: u8 = OrganeOwner::EnemyOwner as u8;
+
+/// One line of the referee's entity list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entity {
+    pub x: u8,
+    pub y: u8,
+    pub entity_type: String,
+    pub owner: i32,
+    pub organ_id: i32,
+    pub organ_dir: String,
+    pub organ_parent_id: i32,
+    pub organ_root_id: i32,
+}
+
+impl Entity {
+    pub fn from_line(line: &str) -> Entity {
+        let inputs = line.split_whitespace().collect::<Vec<_>>();
+        Entity {
+            x: inputs[0].parse().unwrap(),
+            y: inputs[1].parse().unwrap(),
+            entity_type: inputs[2].to_string(),
+            owner: inputs[3].parse().unwrap(),
+            organ_id: inputs[4].parse().unwrap(),
+            organ_dir: inputs[5].to_string(),
+            organ_parent_id: inputs[6].parse().unwrap(),
+            organ_root_id: inputs[7].parse().unwrap(),
+        }
+    }
+
+    pub fn get_coord(&self) -> Coord {
+        coord::new(self.x, self.y)
+    }
+}
+
+/// Everything known at the start of a turn.
+#[derive(Clone)]
+pub struct GameState {
+    pub grid: Grid,
+    /// Indexed by player id, `MY_ID` or `OPPONENT_ID`.
+    pub players: [Player; 2],
+    pub turn: u32,
+    pub required_actions_count: usize,
+    organ_ids: HashMap<Coord, i32>,
+    next_organ_id: i32,
+}
+
+impl GameState {
+    pub fn new(width: u8, height: u8) -> Self {
+        GameState {
+            grid: Grid::new(width, height),
+            players: [Player::new(0), Player::new(1)],
+            turn: 0,
+            required_actions_count: 1,
+            organ_ids: HashMap::new(),
+            next_organ_id: 1,
+        }
+    }
+
+    pub fn from_entities(
+        width: u8,
+        height: u8,
+        entities: &[Entity],
+        wallets: [ProteinWallet; 2],
+        required_actions_count: usize,
+    ) -> Self {
+        let mut state = GameState::new(width, height);
+        state.required_actions_count = required_actions_count;
+        for (id, wallet) in wallets.into_iter().enumerate() {
+            state.players[id].set_wallet(wallet);
+        }
+        let coords_by_id: HashMap<i32, Coord> = entities
+            .iter()
+            .filter(|entity| entity.owner >= 0)
+            .map(|entity| (entity.organ_id, entity.get_coord()))
+            .collect();
+        let mut organs: Vec<&Entity> = Vec::new();
+        for entity in entities {
+            match entity.entity_type.as_str() {
+                "WALL" => state.grid.set_cell(entity.x, entity.y, cell::new_wall()),
+                "A" | "B" | "C" | "D" => state.grid.set_cell(
+                    entity.x,
+                    entity.y,
+                    cell::new(false, Protein::from_str(&entity.entity_type), None),
+                ),
+                _ => organs.push(entity),
+            }
+        }
+        organs.sort_by_key(|entity| entity.organ_id);
+        for entity in organs {
+            let coord = entity.get_coord();
+            let owner = entity.owner as u8;
+            let organ = organ::new(
+                owner,
+                OrganType::from_str(&entity.entity_type),
+                OrganDirection::from_str(&entity.organ_dir),
+                coords_by_id[&entity.organ_root_id],
+            );
+            match coords_by_id.get(&entity.organ_parent_id) {
+                Some(&parent_coord) if !organ::is_root(organ) => {
+                    state.grid.add_organ_with_parent(coord, organ, parent_coord)
+                }
+                _ => state.grid.add_organ(coord, organ),
+            }
+            state.players[owner as usize].add_organ(coord, organ);
+            state.organ_ids.insert(coord, entity.organ_id);
+            state.next_organ_id = state.next_organ_id.max(entity.organ_id + 1);
+        }
+        state
+    }
+
+    pub fn get_me(&self) -> &Player {
+        &self.players[MY_ID as usize]
+    }
+
+    pub fn get_opponent(&self) -> &Player {
+        &self.players[OPPONENT_ID as usize]
+    }
+
+    /// Grows `organ` on `coord` with a fresh organ id, as the referee would.
+    pub fn add_organ(&mut self, coord: Coord, organ: Organ) {
+        self.grid.add_organ(coord, organ);
+        self.players[organ::get_owner(organ) as usize].add_organ(coord, organ);
+        self.organ_ids.insert(coord, self.next_organ_id);
+        self.next_organ_id += 1;
+    }
+
+    /// Removes the organ on `coord` with its whole subtree.
+    pub fn remove_organ(&mut self, coord: Coord) {
+        for dead in self.grid.get_subtree(coord) {
+            let organ = cell::get_organ(self.grid.get_cell_from_coord(dead)).unwrap();
+            self.players[organ::get_owner(organ) as usize].remove_organ(dead);
+            self.organ_ids.remove(&dead);
+        }
+        self.grid.remove_organ(coord);
+    }
+
+    /// Identifies positions reached by different action orders, organ ids aside.
+    pub fn get_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.grid.get_hash().hash(&mut hasher);
+        for player in self.players.iter() {
+            player.get_wallet().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    pub fn get_organ_id(&self, coord: Coord) -> Option<i32> {
+        self.organ_ids.get(&coord).copied()
+    }
+
+    /// Roots of `owner`, in the order the referee expects one action per organism.
+    pub fn get_roots_in_order(&self, owner: u8) -> Vec<Coord> {
+        let mut roots: Vec<Coord> = self.players[owner as usize]
+            .get_roots()
+            .iter()
+            .copied()
+            .collect();
+        roots.sort_by_key(|&root| (self.get_organ_id(root).unwrap_or(i32::MAX), root));
+        roots
+    }
+
+    /// Entity list the referee would send for this state.
+    pub fn get_entities(&self) -> Vec<Entity> {
+        self.grid
+            .iter_cells()
+            .filter_map(|(coord, content)| {
+                let (entity_type, owner, organ_dir, parent, root) = match content {
+                    CellContent::Empty => return None,
+                    CellContent::Wall => ("WALL".to_string(), -1, 'X', None, None),
+                    CellContent::Protein(source) => {
+                        (source.protein.to_str().to_string(), -1, 'X', None, None)
+                    }
+                    CellContent::Organ(organ) => (
+                        organ.organ_type.to_str().to_string(),
+                        organ.owner as i32,
+                        organ.direction.to_char(),
+                        self.grid.get_parent(coord),
+                        Some(organ.root_coord),
+                    ),
+                };
+                let get_id = |coord: Option<Coord>| coord.and_then(|c| self.get_organ_id(c));
+                Some(Entity {
+                    x: coord::x(coord),
+                    y: coord::y(coord),
+                    entity_type,
+                    owner,
+                    organ_id: self.get_organ_id(coord).unwrap_or(0),
+                    organ_dir: organ_dir.to_string(),
+                    organ_parent_id: get_id(parent).unwrap_or(0),
+                    organ_root_id: get_id(root).unwrap_or(0),
+                })
+            })
+            .collect()
+    }
+
+    /// Same position seen by the other player, so a strategy playing `MY_ID` can play both.
+    pub fn with_swapped_players(&self) -> GameState {
+        let mut entities = self.get_entities();
+        for entity in entities.iter_mut().filter(|entity| entity.owner >= 0) {
+            entity.owner = 1 - entity.owner;
+        }
+        let mut state = GameState::from_entities(
+            self.grid.width,
+            self.grid.height,
+            &entities,
+            [self.players[1].get_wallet(), self.players[0].get_wallet()],
+            self.required_actions_count,
+        );
+        state.turn = self.turn;
+        state
+    }
+}
+
+pub fn wallet_from_counts(a: u32, b: u32, c: u32, d: u32) -> ProteinWallet {
+    let mut wallet = protein_wallet::new();
+    protein_wallet::add(&mut wallet, Protein::A, a);
+    protein_wallet::add(&mut wallet, Protein::B, b);
+    protein_wallet::add(&mut wallet, Protein::C, c);
+    protein_wallet::add(&mut wallet, Protein::D, d);
+    wallet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entities() -> Vec<Entity> {
+        [
+            "0 0 WALL -1 0 X 0 0",
+            "3 1 C -1 0 X 0 0",
+            "1 1 BASIC 1 4 E 1 1",
+            "0 1 ROOT 1 1 N 0 1",
+            "1 2 HARVESTER 1 5 E 4 1",
+            "5 1 ROOT 0 2 N 0 2",
+            "6 1 ROOT 1 3 N 0 3",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect()
+    }
+
+    #[test]
+    fn test_wallet_from_counts_saturates() {
+        let wallet = wallet_from_counts(300, 2, 255, 1000);
+
+        assert_eq!(protein_wallet::get(wallet, Protein::A), u8::MAX);
+        assert_eq!(protein_wallet::get(wallet, Protein::B), 2);
+        assert_eq!(protein_wallet::get(wallet, Protein::C), u8::MAX);
+        assert_eq!(protein_wallet::get(wallet, Protein::D), u8::MAX);
+    }
+
+    #[test]
+    fn test_entity_from_line() {
+        let entity = Entity::from_line("1 2 HARVESTER 1 5 E 4 1\n");
+        assert_eq!(entity.get_coord(), coord::new(1, 2));
+        assert_eq!(entity.entity_type, "HARVESTER");
+        assert_eq!(entity.owner, 1);
+        assert_eq!(entity.organ_id, 5);
+        assert_eq!(entity.organ_dir, "E");
+        assert_eq!(entity.organ_parent_id, 4);
+        assert_eq!(entity.organ_root_id, 1);
+    }
+
+    #[test]
+    fn test_from_entities() {
+        let state =
+            GameState::from_entities(8, 3, &entities(), [0, wallet_from_counts(1, 2, 3, 4)], 2);
+
+        assert!(cell::is_obstacle(state.grid.get_cell(0, 0)));
+        assert_eq!(
+            cell::get_protein(state.grid.get_cell(3, 1)),
+            Some(Protein::C)
+        );
+        let harvester = cell::get_organ(state.grid.get_cell(1, 2)).unwrap();
+        assert_eq!(organ::get_type(harvester), OrganType::Harvester);
+        assert_eq!(organ::get_root_coord(harvester), coord::new(0, 1));
+        assert_eq!(state.grid.get_children(coord::new(1, 1)).unwrap().len(), 1);
+        assert_eq!(state.get_me().get_score(), 4);
+        assert_eq!(state.get_opponent().get_score(), 1);
+        assert_eq!(state.get_me().get_nb_protein(Protein::D), 4);
+        assert_eq!(state.get_organ_id(coord::new(1, 2)), Some(5));
+        assert_eq!(state.required_actions_count, 2);
+    }
+
+    #[test]
+    fn test_get_roots_in_order() {
+        let state = GameState::from_entities(8, 3, &entities(), [0, 0], 2);
+
+        assert_eq!(
+            state.get_roots_in_order(MY_ID),
+            vec![coord::new(0, 1), coord::new(6, 1)]
+        );
+        assert_eq!(
+            state.get_roots_in_order(OPPONENT_ID),
+            vec![coord::new(5, 1)]
+        );
+    }
+
+    #[test]
+    fn test_get_hash() {
+        let state = GameState::from_entities(8, 3, &entities(), [0, 0], 1);
+        let mut other = state.clone();
+
+        assert_eq!(state.get_hash(), other.get_hash());
+        other.players[MY_ID as usize].add_protein(Protein::A, 1);
+        assert_ne!(state.get_hash(), other.get_hash());
+        let mut other = state.clone();
+        other.add_organ(
+            coord::new(0, 2),
+            organ::new(
+                MY_ID,
+                OrganType::Basic,
+                OrganDirection::North,
+                coord::new(0, 1),
+            ),
+        );
+        assert_ne!(state.get_hash(), other.get_hash());
+    }
+
+    #[test]
+    fn test_add_and_remove_organ() {
+        let mut state = GameState::from_entities(8, 3, &entities(), [0, 0], 1);
+        let root = coord::new(0, 1);
+
+        state.add_organ(
+            coord::new(0, 2),
+            organ::new(MY_ID, OrganType::Basic, OrganDirection::North, root),
+        );
+        assert_eq!(state.get_organ_id(coord::new(0, 2)), Some(6));
+        assert_eq!(state.get_me().get_score(), 5);
+
+        // the new organ grew from the harvester, so it dies with it
+        state.remove_organ(coord::new(1, 1));
+        assert_eq!(state.get_me().get_score(), 2);
+        assert_eq!(state.get_organ_id(coord::new(1, 2)), None);
+        assert!(cell::is_empty(state.grid.get_cell(1, 2)));
+        assert!(cell::is_empty(state.grid.get_cell(0, 2)));
+    }
+
+    #[test]
+    fn test_get_entities_round_trip() {
+        let state = GameState::from_entities(8, 3, &entities(), [0, 0], 1);
+        let mut expected = entities();
+        expected.sort_by_key(|entity| (entity.y, entity.x));
+
+        assert_eq!(state.get_entities(), expected);
+    }
+
+    #[test]
+    fn test_with_swapped_players() {
+        let state = GameState::from_entities(
+            8,
+            3,
+            &entities(),
+            [
+                wallet_from_counts(1, 0, 0, 0),
+                wallet_from_counts(0, 2, 0, 0),
+            ],
+            1,
+        );
+        let swapped = state.with_swapped_players();
+
+        assert_eq!(swapped.get_me().get_score(), 1);
+        assert_eq!(swapped.get_opponent().get_score(), 4);
+        assert_eq!(
+            swapped.get_me().get_wallet(),
+            wallet_from_counts(1, 0, 0, 0)
+        );
+        assert_eq!(swapped.get_roots_in_order(MY_ID), vec![coord::new(5, 1)]);
+        assert_eq!(swapped.get_organ_id(coord::new(1, 2)), Some(5));
+        assert_eq!(
+            swapped.grid.get_parent(coord::new(1, 2)),
+            Some(coord::new(1, 1))
+        );
+    }
+}
@@ -1,18 +1,24 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
 
 use crate::game_entities::organ_direction::OrganDirection;
 
 use super::{
     cell::{self, Cell},
+    cell_content::{CellContent, OrganEntity, ProteinSource},
     coord::{self, Coord},
     organ::{self, get_type, Organ},
     organ_type::OrganType,
+    protein::Protein,
 };
 
 pub const MAX_WIDTH: usize = 24;
 
 pub const MAX_HEIGHT: usize = 12;
 
+#[derive(Clone)]
 pub struct Grid {
     cells: [Cell; MAX_WIDTH * MAX_HEIGHT],
     cell_connections: HashMap<coord::Coord, HashSet<coord::Coord>>,
@@ -38,6 +44,13 @@ impl Grid {
         }
     }
 
+    /// Hash of the cells only, organ connections follow from them most of the time.
+    pub fn get_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.cells[..self.width as usize * self.height as usize].hash(&mut hasher);
+        hasher.finish()
+    }
+
     pub fn is_in_bounds(&self, x: u8, y: u8) -> bool {
         x < self.width && y < self.height
     }
@@ -50,31 +63,65 @@ impl Grid {
         self.get_cell(coord::x(coord), coord::y(coord))
     }
 
-    pub fn get_adjacent_coords(&self, coord: coord::Coord) -> HashSet<coord::Coord> {
-        let mut adjacents = HashSet::with_capacity(4);
+    /// Adjacent coords in a fixed order, west, east, north then south.
+    pub fn get_adjacent_coords(&self, coord: coord::Coord) -> Vec<coord::Coord> {
+        let mut adjacents = Vec::with_capacity(4);
 
         let x = coord::x(coord);
         let y = coord::y(coord);
 
         if x > 0 && self.is_in_bounds(x - 1, y) {
-            adjacents.insert(coord::new(x - 1, y));
+            adjacents.push(coord::new(x - 1, y));
         }
         if self.is_in_bounds(x + 1, y) {
-            adjacents.insert(coord::new(x + 1, y));
+            adjacents.push(coord::new(x + 1, y));
         }
         if y > 0 && self.is_in_bounds(x, y - 1) {
-            adjacents.insert(coord::new(x, y - 1));
+            adjacents.push(coord::new(x, y - 1));
         }
         if self.is_in_bounds(x, y + 1) {
-            adjacents.insert(coord::new(x, y + 1));
+            adjacents.push(coord::new(x, y + 1));
         }
         adjacents
     }
 
+    /// Neighbour of `coord` in `direction`, None when it leaves the grid.
+    pub fn get_next_coord(&self, coord: Coord, direction: OrganDirection) -> Option<Coord> {
+        let x = coord::x(coord);
+        let y = coord::y(coord);
+        match direction {
+            OrganDirection::North if y > 0 => Some(coord::new(x, y - 1)),
+            OrganDirection::West if x > 0 => Some(coord::new(x - 1, y)),
+            OrganDirection::South if y + 1 < self.height => Some(coord::new(x, y + 1)),
+            OrganDirection::East if x + 1 < self.width => Some(coord::new(x + 1, y)),
+            _ => None,
+        }
+    }
+
     pub fn get_children(&self, coord: coord::Coord) -> Option<&HashSet<coord::Coord>> {
         self.cell_connections.get(&coord)
     }
 
+    pub fn get_parent(&self, coord: coord::Coord) -> Option<coord::Coord> {
+        self.cell_connections
+            .iter()
+            .find(|(_, children)| children.contains(&coord))
+            .map(|(&parent, _)| parent)
+    }
+
+    /// `coord` followed by every organ that dies with it.
+    pub fn get_subtree(&self, coord: coord::Coord) -> Vec<coord::Coord> {
+        let mut subtree = vec![coord];
+        let mut index = 0;
+        while index < subtree.len() {
+            if let Some(children) = self.get_children(subtree[index]) {
+                subtree.extend(children.iter().copied());
+            }
+            index += 1;
+        }
+        subtree
+    }
+
     pub fn get_adjacent_cells(&self, coord: coord::Coord) -> HashSet<Cell> {
         let mut adjacents = HashSet::with_capacity(4);
 
@@ -101,29 +148,24 @@ impl Grid {
     }
 
     fn get_one_adjacent_organ(&self, coord: Coord, owner: u8, root_coord: Coord) -> Coord {
-        let x = coord::x(coord);
-        let y = coord::y(coord);
-        if x > 0 && cell::is_owned_and_rooted_by(self.get_cell(x - 1, y), owner, root_coord) {
-            return coord::new(x - 1, y);
-        }
-        if x < self.width - 1
-            && cell::is_owned_and_rooted_by(self.get_cell(x + 1, y), owner, root_coord)
-        {
-            return coord::new(x + 1, y);
-        }
-        if y > 0 && cell::is_owned_and_rooted_by(self.get_cell(x, y - 1), owner, root_coord) {
-            return coord::new(x, y - 1);
-        }
-        if y < self.height - 1
-            && cell::is_owned_and_rooted_by(self.get_cell(x, y + 1), owner, root_coord)
-        {
-            return coord::new(x, y + 1);
-        }
-        panic!(
-            "\x1b[31mNo adjacent organ found for coord x: {:?} y: {:?}\x1b[0m",
-            coord::x(coord),
-            coord::y(coord)
-        );
+        self.find_parent(coord, owner, root_coord)
+            .unwrap_or_else(|| {
+                panic!(
+                    "\x1b[31mNo adjacent organ found for coord x: {:?} y: {:?}\x1b[0m",
+                    coord::x(coord),
+                    coord::y(coord)
+                )
+            })
+    }
+
+    /// Organ of the organism a new organ on `coord` is attached to, the first adjacent one
+    /// in west, east, north, south order. GROW commands name the same parent.
+    pub fn find_parent(&self, coord: Coord, owner: u8, root_coord: Coord) -> Option<Coord> {
+        self.get_adjacent_coords(coord)
+            .into_iter()
+            .find(|&adjacent| {
+                cell::is_owned_and_rooted_by(self.get_cell_from_coord(adjacent), owner, root_coord)
+            })
     }
 
     pub fn add_organ(&mut self, coord: Coord, organ: Organ) {
@@ -141,26 +183,31 @@ impl Grid {
             organ::get_root_coord(organ),
         );
 
-        let connections = self
-            .cell_connections
-            .entry(parent_cell)
-            .or_insert(HashSet::new());
+        let connections = self.cell_connections.entry(parent_cell).or_default();
         connections.insert(coord);
     }
 
+    /// Same as `add_organ` with a known parent, as given by the referee.
+    pub fn add_organ_with_parent(&mut self, coord: Coord, organ: Organ, parent_coord: Coord) {
+        let x = coord::x(coord);
+        let y = coord::y(coord);
+        self.set_cell(x, y, cell::new(false, None, Some(organ)));
+        self.cell_connections
+            .entry(parent_coord)
+            .or_default()
+            .insert(coord);
+    }
+
     pub fn remove_organ(&mut self, coord: Coord) {
         let x = coord::x(coord);
         let y = coord::y(coord);
         let cell = self.get_cell(x, y);
         if let Some(organ) = cell::get_organ(cell) {
             if OrganType::Root != get_type(organ) {
-                let parent_cell = self.get_one_adjacent_organ(
-                    coord,
-                    organ::get_owner(organ),
-                    organ::get_root_coord(organ),
-                );
-                let connections = self.cell_connections.get_mut(&parent_cell).unwrap();
-                connections.retain(|&c| c != coord);
+                if let Some(parent_cell) = self.get_parent(coord) {
+                    let connections = self.cell_connections.get_mut(&parent_cell).unwrap();
+                    connections.retain(|&c| c != coord);
+                }
             }
         }
         self.remove_children(coord);
@@ -239,7 +286,7 @@ impl Grid {
         let x = coord::x(dest);
         let y = coord::y(dest);
         (self.is_in_bounds(x, y)
-            && cell::is_empty(self.get_cell(x, y))
+            && cell::is_growable(self.get_cell(x, y))
             && !self.is_canceled_by_tentacle(x, y, organ::get_owner(organ)))
             && (OrganType::Root == get_type(organ)
                 || self.contains_an_adjacent_organ(x, y, organ::get_owner(organ)))
@@ -249,7 +296,7 @@ impl Grid {
         let x = coord::x(dest);
         let y = coord::y(dest);
         (self.is_in_bounds(x, y)
-            && cell::is_empty(self.get_cell(x, y))
+            && cell::is_growable(self.get_cell(x, y))
             && !self.is_canceled_by_tentacle(x, y, organ::get_owner(organ)))
             && (OrganType::Root == get_type(organ)
                 || self.contains_an_adjacent_organ_with_same_root(
@@ -259,7 +306,7 @@ impl Grid {
                 ))
     }
 
-    fn is_canceled_by_tentacle(&self, x: u8, y: u8, owner: u8) -> bool {
+    pub fn is_canceled_by_tentacle(&self, x: u8, y: u8, owner: u8) -> bool {
         let initial_coord = coord::new(x, y);
         (x > 0 && self.is_front_of_enemy_tentacle(initial_coord, coord::new(x - 1, y), owner))
             || ((x < self.width - 1)
@@ -362,11 +409,84 @@ impl Grid {
             || (y > 0 && cell::has_root_coord(self.get_cell(x, y - 1), root_coord))
             || (y < self.height - 1 && cell::has_root_coord(self.get_cell(x, y + 1), root_coord))
     }
+
+    pub fn iter_coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| coord::new(x, y)))
+    }
+
+    pub fn iter_cells(&self) -> impl Iterator<Item = (Coord, CellContent)> + '_ {
+        self.iter_coords().map(move |coord| {
+            (
+                coord,
+                CellContent::from_cell(coord, self.get_cell_from_coord(coord)),
+            )
+        })
+    }
+
+    pub fn iter_organs(&self) -> impl Iterator<Item = OrganEntity> + '_ {
+        self.iter_cells().filter_map(|(_, content)| match content {
+            CellContent::Organ(organ) => Some(organ),
+            _ => None,
+        })
+    }
+
+    pub fn iter_organs_of_owner(&self, owner: u8) -> impl Iterator<Item = OrganEntity> + '_ {
+        self.iter_organs().filter(move |organ| organ.owner == owner)
+    }
+
+    pub fn iter_organs_of_root(&self, root_coord: Coord) -> impl Iterator<Item = OrganEntity> + '_ {
+        self.iter_organs()
+            .filter(move |organ| organ.root_coord == root_coord)
+    }
+
+    pub fn iter_roots(&self, owner: u8) -> impl Iterator<Item = OrganEntity> + '_ {
+        self.iter_organs_of_owner(owner)
+            .filter(|organ| organ.organ_type == OrganType::Root)
+    }
+
+    pub fn iter_protein_sources(&self) -> impl Iterator<Item = ProteinSource> + '_ {
+        self.iter_cells().filter_map(|(_, content)| match content {
+            CellContent::Protein(source) => Some(source),
+            _ => None,
+        })
+    }
+
+    pub fn iter_protein_sources_of(
+        &self,
+        protein: Protein,
+    ) -> impl Iterator<Item = ProteinSource> + '_ {
+        self.iter_protein_sources()
+            .filter(move |source| source.protein == protein)
+    }
+
+    pub fn iter_empty_cells(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.iter_coords()
+            .filter(|&coord| cell::is_empty(self.get_cell_from_coord(coord)))
+    }
+
+    /// Cells where `owner` can legally grow a new organ from any of its organisms.
+    pub fn iter_frontier(&self, owner: u8) -> impl Iterator<Item = Coord> + '_ {
+        let organ = organ::new(owner, OrganType::Basic, OrganDirection::North, 0);
+        self.iter_coords()
+            .filter(move |&coord| self.can_add_organ_without_root_coord(coord, organ))
+    }
+
+    /// Cells where the organism rooted at `root_coord` can legally grow a new organ.
+    pub fn iter_root_frontier(
+        &self,
+        owner: u8,
+        root_coord: Coord,
+    ) -> impl Iterator<Item = Coord> + '_ {
+        let organ = organ::new(owner, OrganType::Basic, OrganDirection::North, root_coord);
+        self.iter_coords()
+            .filter(move |&coord| self.can_add_organ_with_root_coord(coord, organ))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::game_entities::{cell, organ_direction::OrganDirection, protein::Protein};
+    use crate::game_entities::{cell, organ_direction::OrganDirection};
 
     use super::*;
 
@@ -376,9 +496,9 @@ mod tests {
 
         grid.set_cell(0, 0, cell::new(true, None, None));
 
-        assert_eq!(cell::is_obstacle(grid.get_cell(0, 0)), true);
-        assert_eq!(cell::is_obstacle(grid.get_cell(1, 1)), false);
-        assert_eq!(cell::is_obstacle(grid.get_cell(2, 2)), false);
+        assert!(cell::is_obstacle(grid.get_cell(0, 0)));
+        assert!(!cell::is_obstacle(grid.get_cell(1, 1)));
+        assert!(!cell::is_obstacle(grid.get_cell(2, 2)));
     }
 
     #[test]
@@ -393,18 +513,18 @@ mod tests {
     fn test_is_in_bounds() {
         let grid = Grid::new(3, 3);
 
-        assert_eq!(grid.is_in_bounds(0, 0), true);
-        assert_eq!(grid.is_in_bounds(2, 2), true);
-        assert_eq!(grid.is_in_bounds(3, 3), false);
+        assert!(grid.is_in_bounds(0, 0));
+        assert!(grid.is_in_bounds(2, 2));
+        assert!(!grid.is_in_bounds(3, 3));
     }
 
     #[test]
     fn test_is_in_bounds_coord() {
         let grid = Grid::new(3, 3);
 
-        assert_eq!(grid.is_in_bounds_coord(coord::new(0, 0)), true);
-        assert_eq!(grid.is_in_bounds_coord(coord::new(2, 2)), true);
-        assert_eq!(grid.is_in_bounds_coord(coord::new(3, 3)), false);
+        assert!(grid.is_in_bounds_coord(coord::new(0, 0)));
+        assert!(grid.is_in_bounds_coord(coord::new(2, 2)));
+        assert!(!grid.is_in_bounds_coord(coord::new(3, 3)));
     }
 
     #[test]
@@ -413,18 +533,15 @@ mod tests {
 
         grid.set_cell(0, 0, cell::new(true, None, None));
 
-        assert_eq!(
-            cell::is_obstacle(grid.get_cell_from_coord(coord::new(0, 0))),
-            true
-        );
-        assert_eq!(
-            cell::is_obstacle(grid.get_cell_from_coord(coord::new(1, 1))),
-            false
-        );
-        assert_eq!(
-            cell::is_obstacle(grid.get_cell_from_coord(coord::new(2, 2))),
-            false
-        );
+        assert!(cell::is_obstacle(
+            grid.get_cell_from_coord(coord::new(0, 0))
+        ));
+        assert!(!cell::is_obstacle(
+            grid.get_cell_from_coord(coord::new(1, 1))
+        ));
+        assert!(!cell::is_obstacle(
+            grid.get_cell_from_coord(coord::new(2, 2))
+        ));
     }
 
     #[test]
@@ -433,7 +550,7 @@ mod tests {
 
         grid.set_cell(0, 0, cell::new(true, None, None));
 
-        assert_eq!(cell::is_obstacle(grid.get_cell(0, 0)), true);
+        assert!(cell::is_obstacle(grid.get_cell(0, 0)));
     }
 
     #[test]
@@ -442,7 +559,7 @@ mod tests {
 
         grid.set_cell(0, 0, cell::new(true, None, None));
 
-        assert_eq!(cell::is_obstacle(grid.get_cell(0, 0)), true);
+        assert!(cell::is_obstacle(grid.get_cell(0, 0)));
     }
 
     #[test]
@@ -451,7 +568,7 @@ mod tests {
 
         grid.set_cell(0, 0, cell::new(true, None, None));
 
-        assert_eq!(cell::is_obstacle(grid.get_cell(0, 0)), true);
+        assert!(cell::is_obstacle(grid.get_cell(0, 0)));
     }
 
     #[test]
@@ -466,6 +583,32 @@ mod tests {
         assert!(adjacent_coords.contains(&coord::new(1, 2)));
     }
 
+    #[test]
+    fn test_get_next_coord() {
+        let grid = Grid::new(3, 3);
+
+        assert_eq!(
+            grid.get_next_coord(coord::new(1, 1), OrganDirection::North),
+            Some(coord::new(1, 0))
+        );
+        assert_eq!(
+            grid.get_next_coord(coord::new(1, 1), OrganDirection::East),
+            Some(coord::new(2, 1))
+        );
+        assert_eq!(
+            grid.get_next_coord(coord::new(0, 0), OrganDirection::West),
+            None
+        );
+        assert_eq!(
+            grid.get_next_coord(coord::new(2, 2), OrganDirection::South),
+            None
+        );
+        assert_eq!(
+            grid.get_next_coord(coord::new(1, 1), OrganDirection::X),
+            None
+        );
+    }
+
     #[test]
     fn test_get_adjacent_cells() {
         let mut grid = Grid::new(3, 3);
@@ -475,12 +618,10 @@ mod tests {
         grid.set_cell(1, 2, cell::new(true, None, None));
         grid.set_cell(2, 1, cell::new(true, None, None));
 
-        assert_eq!(
-            grid.get_adjacent_cells(coord::new(1, 1))
-                .iter()
-                .all(|&cell| cell::is_obstacle(cell)),
-            true
-        );
+        assert!(grid
+            .get_adjacent_cells(coord::new(1, 1))
+            .iter()
+            .all(|&cell| cell::is_obstacle(cell)));
     }
 
     #[test]
@@ -509,42 +650,15 @@ mod tests {
 
         let default_organ = organ::new(0, OrganType::Root, OrganDirection::North, 0);
 
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(0, 0), default_organ),
-            true
-        );
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(1, 0), default_organ),
-            true
-        );
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(2, 0), default_organ),
-            true
-        );
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(0, 1), default_organ),
-            true
-        );
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(1, 1), default_organ),
-            true
-        );
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(2, 1), default_organ),
-            true
-        );
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(0, 2), default_organ),
-            true
-        );
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(1, 2), default_organ),
-            true
-        );
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(2, 2), default_organ),
-            true
-        );
+        assert!(grid.can_add_organ_without_root_coord(coord::new(0, 0), default_organ));
+        assert!(grid.can_add_organ_without_root_coord(coord::new(1, 0), default_organ));
+        assert!(grid.can_add_organ_without_root_coord(coord::new(2, 0), default_organ));
+        assert!(grid.can_add_organ_without_root_coord(coord::new(0, 1), default_organ));
+        assert!(grid.can_add_organ_without_root_coord(coord::new(1, 1), default_organ));
+        assert!(grid.can_add_organ_without_root_coord(coord::new(2, 1), default_organ));
+        assert!(grid.can_add_organ_without_root_coord(coord::new(0, 2), default_organ));
+        assert!(grid.can_add_organ_without_root_coord(coord::new(1, 2), default_organ));
+        assert!(grid.can_add_organ_without_root_coord(coord::new(2, 2), default_organ));
     }
 
     #[test]
@@ -553,10 +667,7 @@ mod tests {
 
         let default_organ = organ::new(0, OrganType::Basic, OrganDirection::North, 0);
 
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(3, 3), default_organ),
-            false
-        );
+        assert!(!grid.can_add_organ_without_root_coord(coord::new(3, 3), default_organ));
     }
 
     #[test]
@@ -565,10 +676,7 @@ mod tests {
 
         let default_organ = organ::new(0, OrganType::Basic, OrganDirection::North, 0);
 
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(2, 2), default_organ),
-            false
-        );
+        assert!(!grid.can_add_organ_without_root_coord(coord::new(2, 2), default_organ));
     }
 
     #[test]
@@ -577,10 +685,7 @@ mod tests {
 
         let default_organ = organ::new(0, OrganType::Root, OrganDirection::North, 0);
 
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(2, 2), default_organ),
-            true
-        );
+        assert!(grid.can_add_organ_without_root_coord(coord::new(2, 2), default_organ));
     }
 
     #[test]
@@ -591,10 +696,7 @@ mod tests {
 
         grid.set_cell(2, 3, cell::new(false, None, Some(tentacle_rival)));
 
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(2, 2), default_organ),
-            false
-        );
+        assert!(!grid.can_add_organ_without_root_coord(coord::new(2, 2), default_organ));
     }
 
     #[test]
@@ -605,10 +707,7 @@ mod tests {
 
         grid.set_cell(2, 3, cell::new(false, None, Some(tentacle_rival)));
 
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(2, 2), default_organ),
-            true
-        );
+        assert!(grid.can_add_organ_without_root_coord(coord::new(2, 2), default_organ));
     }
 
     #[test]
@@ -619,10 +718,7 @@ mod tests {
 
         let default_organ = organ::new(0, OrganType::Basic, OrganDirection::North, 0);
 
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(0, 0), default_organ),
-            false
-        );
+        assert!(!grid.can_add_organ_without_root_coord(coord::new(0, 0), default_organ));
     }
 
     #[test]
@@ -633,10 +729,7 @@ mod tests {
 
         let default_organ = organ::new(0, OrganType::Basic, OrganDirection::North, 0);
 
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(1, 0), default_organ),
-            false
-        );
+        assert!(!grid.can_add_organ_without_root_coord(coord::new(1, 0), default_organ));
     }
 
     #[test]
@@ -646,10 +739,20 @@ mod tests {
 
         grid.set_cell(0, 0, cell::new(false, None, Some(default_organ)));
 
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(1, 0), default_organ),
-            true
-        );
+        assert!(grid.can_add_organ_without_root_coord(coord::new(1, 0), default_organ));
+    }
+
+    #[test]
+    fn test_can_grow_on_protein_source() {
+        let mut grid = Grid::new(3, 3);
+        let root_organ = organ::new(1, OrganType::Root, OrganDirection::North, 0);
+        let default_organ = organ::new(1, OrganType::Basic, OrganDirection::North, 0);
+
+        grid.set_cell(0, 0, cell::new(false, None, Some(root_organ)));
+        grid.set_cell(1, 0, cell::new(false, Some(Protein::A), None));
+
+        assert!(grid.can_add_organ_without_root_coord(coord::new(1, 0), default_organ));
+        assert!(grid.can_add_organ_with_root_coord(coord::new(1, 0), default_organ));
     }
 
     #[test]
@@ -660,10 +763,7 @@ mod tests {
 
         grid.set_cell(0, 0, cell::new(false, None, Some(default_organ0)));
 
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(3, 3), default_organ1),
-            false
-        );
+        assert!(!grid.can_add_organ_without_root_coord(coord::new(3, 3), default_organ1));
     }
 
     #[test]
@@ -676,14 +776,8 @@ mod tests {
         grid.set_cell(0, 0, cell::new(false, None, Some(root_organ)));
         grid.set_cell(0, 1, cell::new(false, None, Some(tentacle_organ)));
 
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(0, 2), root_organ1),
-            false
-        );
-        assert_eq!(
-            grid.can_add_organ_without_root_coord(coord::new(0, 2), root_organ),
-            true
-        );
+        assert!(!grid.can_add_organ_without_root_coord(coord::new(0, 2), root_organ1));
+        assert!(grid.can_add_organ_without_root_coord(coord::new(0, 2), root_organ));
     }
 
     #[test]
@@ -704,12 +798,30 @@ mod tests {
         grid.add_organ(coord::new(1, 1), default_organ);
 
         let connections = grid.cell_connections.get(&coord::new(1, 1));
-        assert_eq!(connections.is_none(), true);
+        assert!(connections.is_none());
 
         let connections = grid.cell_connections.get(&coord::new(0, 0)).unwrap();
         assert_eq!(connections.len(), 2);
     }
 
+    #[test]
+    fn test_add_organ_with_parent() {
+        let mut grid = Grid::new(3, 3);
+        let root_coord = coord::new(0, 0);
+        let root_organ = organ::new(0, OrganType::Root, OrganDirection::North, root_coord);
+        let default_organ = organ::new(0, OrganType::Basic, OrganDirection::North, root_coord);
+
+        grid.add_organ(root_coord, root_organ);
+        grid.add_organ(coord::new(1, 0), default_organ);
+        grid.add_organ_with_parent(coord::new(1, 1), default_organ, coord::new(1, 0));
+
+        assert_eq!(
+            grid.get_children(coord::new(1, 0)),
+            Some(&HashSet::from([coord::new(1, 1)]))
+        );
+        assert_eq!(grid.get_children(coord::new(0, 0)).unwrap().len(), 1);
+    }
+
     #[test]
     pub fn test_remove_organ() {
         let mut grid = Grid::new(3, 3);
@@ -723,17 +835,39 @@ mod tests {
 
         grid.remove_organ(coord::new(1, 0));
 
-        assert_eq!(cell::is_empty(grid.get_cell(1, 0)), true);
+        assert!(cell::is_empty(grid.get_cell(1, 0)));
 
         let connections = grid.cell_connections.get(&coord::new(0, 0)).unwrap();
         assert_eq!(connections.len(), 1);
 
         grid.remove_organ(coord::new(0, 0));
 
-        assert_eq!(grid.cells.iter().all(|&cell| cell::is_empty(cell)), true);
+        assert!(grid.cells.iter().all(|&cell| cell::is_empty(cell)));
 
         let connections = grid.cell_connections.get(&coord::new(0, 0));
-        assert_eq!(connections.is_none(), true);
+        assert!(connections.is_none());
+    }
+
+    #[test]
+    fn test_get_subtree_and_parent() {
+        let mut grid = Grid::new(3, 3);
+        let root_organ = organ::new(0, OrganType::Root, OrganDirection::North, 0);
+        let default_organ = organ::new(0, OrganType::Basic, OrganDirection::North, 0);
+
+        grid.add_organ(coord::new(0, 0), root_organ);
+        grid.add_organ(coord::new(1, 0), default_organ);
+        grid.add_organ(coord::new(0, 1), default_organ);
+        grid.add_organ_with_parent(coord::new(1, 1), default_organ, coord::new(1, 0));
+
+        let subtree: HashSet<Coord> = grid.get_subtree(coord::new(1, 0)).into_iter().collect();
+        assert_eq!(subtree, HashSet::from([coord::new(1, 0), coord::new(1, 1)]));
+        assert_eq!(grid.get_subtree(coord::new(0, 0)).len(), 4);
+        assert_eq!(grid.get_parent(coord::new(1, 1)), Some(coord::new(1, 0)));
+        assert_eq!(grid.get_parent(coord::new(0, 0)), None);
+
+        grid.remove_organ(coord::new(1, 1));
+        assert_eq!(grid.get_children(coord::new(1, 0)).unwrap().len(), 0);
+        assert!(cell::is_empty(grid.get_cell(1, 1)));
     }
 
     #[test]
@@ -750,14 +884,110 @@ mod tests {
 
         grid.remove_organ(coord::new(1, 1));
 
-        assert_eq!(cell::is_empty(grid.get_cell(0, 0)), false);
-        assert_eq!(cell::is_empty(grid.get_cell(0, 1)), false);
-        assert_eq!(cell::is_empty(grid.get_cell(1, 1)), true);
-        assert_eq!(cell::is_empty(grid.get_cell(1, 0)), false);
-        assert_eq!(cell::is_empty(grid.get_cell(2, 1)), false);
-        assert_eq!(cell::is_empty(grid.get_cell(1, 2)), false);
+        assert!(!cell::is_empty(grid.get_cell(0, 0)));
+        assert!(!cell::is_empty(grid.get_cell(0, 1)));
+        assert!(cell::is_empty(grid.get_cell(1, 1)));
+        assert!(!cell::is_empty(grid.get_cell(1, 0)));
+        assert!(!cell::is_empty(grid.get_cell(2, 1)));
+        assert!(!cell::is_empty(grid.get_cell(1, 2)));
 
         let connections = grid.cell_connections.get(&coord::new(0, 0));
-        assert_eq!(connections.is_none(), true);
+        assert!(connections.is_none());
+    }
+
+    fn build_iter_grid() -> Grid {
+        let mut grid = Grid::new(8, 3);
+        let root0 = coord::new(1, 1);
+        let root1 = coord::new(6, 1);
+        grid.add_organ(
+            root0,
+            organ::new(0, OrganType::Root, OrganDirection::North, root0),
+        );
+        grid.add_organ(
+            coord::new(2, 1),
+            organ::new(0, OrganType::Basic, OrganDirection::North, root0),
+        );
+        grid.add_organ(
+            root1,
+            organ::new(1, OrganType::Root, OrganDirection::North, root1),
+        );
+        grid.add_organ(
+            coord::new(5, 1),
+            organ::new(1, OrganType::Harvester, OrganDirection::North, root1),
+        );
+        grid.set_cell(5, 0, cell::new(false, Some(Protein::C), None));
+        grid.set_cell(0, 0, cell::new(false, Some(Protein::A), None));
+        grid.set_cell(3, 1, cell::new_wall());
+        grid
+    }
+
+    #[test]
+    fn test_iter_cells() {
+        let grid = build_iter_grid();
+        let cells: Vec<(Coord, CellContent)> = grid.iter_cells().collect();
+
+        assert_eq!(cells.len(), 24);
+        assert_eq!(cells[0].0, coord::new(0, 0));
+        assert_eq!(cells[11].1, CellContent::Wall);
+        assert_eq!(cells[23].0, coord::new(7, 2));
+    }
+
+    #[test]
+    fn test_iter_organs() {
+        let grid = build_iter_grid();
+
+        assert_eq!(grid.iter_organs().count(), 4);
+        let organs: Vec<OrganEntity> = grid.iter_organs_of_owner(1).collect();
+        assert_eq!(organs.len(), 2);
+        assert_eq!(organs[0].coord, coord::new(5, 1));
+        assert_eq!(organs[0].organ_type, OrganType::Harvester);
+        assert_eq!(organs[0].root_coord, coord::new(6, 1));
+        assert_eq!(grid.iter_organs_of_root(coord::new(1, 1)).count(), 2);
+        let roots: Vec<Coord> = grid.iter_roots(1).map(|organ| organ.coord).collect();
+        assert_eq!(roots, vec![coord::new(6, 1)]);
+    }
+
+    #[test]
+    fn test_iter_protein_sources() {
+        let grid = build_iter_grid();
+
+        assert_eq!(grid.iter_protein_sources().count(), 2);
+        let sources: Vec<ProteinSource> = grid.iter_protein_sources_of(Protein::C).collect();
+        assert_eq!(
+            sources,
+            vec![ProteinSource {
+                coord: coord::new(5, 0),
+                protein: Protein::C
+            }]
+        );
+    }
+
+    #[test]
+    fn test_iter_empty_cells() {
+        let grid = build_iter_grid();
+
+        assert_eq!(grid.iter_empty_cells().count(), 24 - 4 - 2 - 1);
+        assert!(grid.iter_empty_cells().all(|c| c != coord::new(0, 0)));
+    }
+
+    #[test]
+    fn test_iter_frontier() {
+        let grid = build_iter_grid();
+        let frontier: HashSet<Coord> = grid.iter_frontier(0).collect();
+
+        assert_eq!(
+            frontier,
+            HashSet::from([
+                coord::new(1, 0),
+                coord::new(0, 1),
+                coord::new(1, 2),
+                coord::new(2, 0),
+                coord::new(2, 2),
+            ])
+        );
+        let frontier: HashSet<Coord> = grid.iter_root_frontier(1, coord::new(6, 1)).collect();
+        assert!(frontier.contains(&coord::new(5, 0)));
+        assert!(frontier.contains(&coord::new(4, 1)));
+        assert!(!frontier.contains(&coord::new(3, 1)));
     }
 }
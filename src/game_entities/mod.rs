@@ -1,6 +1,8 @@
 pub mod protein_wallet;
 pub mod cell;
+pub mod cell_content;
 pub mod coord;
+pub mod game_state;
 pub mod grid;
 pub mod organ;
 pub mod organ_direction;
@@ -1,4 +1,8 @@
-use super::{coord::Coord, organ_direction::OrganDirection, organ_type::OrganType};
+use super::{
+    coord::{self, Coord},
+    organ_direction::OrganDirection,
+    organ_type::OrganType,
+};
 
 const MASK_PLAYER: Organ = 0b0000_0001;
 const MASK_ORGAN_TYPE: Organ = 0b0001_1110;
@@ -44,7 +48,12 @@ pub fn get_face_coord(organ: Organ, coord: Coord) -> Coord {
 }
 
 pub fn is_faced_to(organ: Organ, organ_coord: Coord, coord: Coord) -> bool {
-    get_face_coord(organ, organ_coord) == coord
+    let is_facing_outside = match get_direction(organ) {
+        OrganDirection::North => coord::y(organ_coord) == 0,
+        OrganDirection::West => coord::x(organ_coord) == 0,
+        _ => false,
+    };
+    !is_facing_outside && get_face_coord(organ, organ_coord) == coord
 }
 
 pub fn is_root(organ: Organ) -> bool {
@@ -69,8 +78,6 @@ pub fn is_basic(organ: Organ) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use crate::game_entities::coord;
-
     use super::*;
 
     #[test]
@@ -117,6 +124,13 @@ mod tests {
         assert!(is_faced_to(organ, organ_coord, coord));
     }
 
+    #[test]
+    fn test_is_faced_to_outside_of_grid() {
+        let organ = new(0, OrganType::Tentacle, OrganDirection::West, 0);
+        let organ_coord = coord::new(0, 1);
+        assert!(!is_faced_to(organ, organ_coord, coord::new(1, 1)));
+    }
+
     #[test]
     fn test_is_root() {
         let organ = new(0, OrganType::Root, OrganDirection::North, 0);
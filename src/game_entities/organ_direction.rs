@@ -10,6 +10,7 @@ pub enum OrganDirection {
 }
 
 impl OrganDirection {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> OrganDirection {
         match s {
             "NORTH" | "N" => OrganDirection::North,
@@ -10,6 +10,7 @@ pub enum OrganType {
 }
 
 impl OrganType {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> OrganType {
         match s {
             "ROOT" => OrganType::Root,
@@ -42,13 +43,14 @@ impl OrganType {
         }
     }
 
+    /// Protein A is stored in the lowest byte, D in the highest.
     pub fn get_cost(&self) -> ProteinWallet {
         match self {
             OrganType::Root => 0x01_01_01_01,
-            OrganType::Basic => 0x01_00_00_00,
+            OrganType::Basic => 0x00_00_00_01,
             OrganType::Harvester => 0x00_01_01_00,
-            OrganType::Sporer => 0x00_00_01_01,
-            OrganType::Tentacle => 0x00_01_00_01,
+            OrganType::Sporer => 0x01_01_00_00,
+            OrganType::Tentacle => 0x01_00_01_00,
         }
     }
 
@@ -65,6 +67,8 @@ impl OrganType {
 
 #[cfg(test)]
 mod tests {
+    use crate::game_entities::{protein::Protein, protein_wallet};
+
     use super::*;
 
     #[test]
@@ -84,4 +88,17 @@ mod tests {
         assert_eq!(OrganType::Sporer.to_str(), "SPORER");
         assert_eq!(OrganType::Tentacle.to_str(), "TENTACLE");
     }
+
+    #[test]
+    fn test_organ_type_get_cost() {
+        let costs = |organ_type: OrganType| {
+            let cost = organ_type.get_cost();
+            [Protein::A, Protein::B, Protein::C, Protein::D].map(|p| protein_wallet::get(cost, p))
+        };
+        assert_eq!(costs(OrganType::Root), [1, 1, 1, 1]);
+        assert_eq!(costs(OrganType::Basic), [1, 0, 0, 0]);
+        assert_eq!(costs(OrganType::Harvester), [0, 1, 1, 0]);
+        assert_eq!(costs(OrganType::Sporer), [0, 0, 1, 1]);
+        assert_eq!(costs(OrganType::Tentacle), [0, 1, 0, 1]);
+    }
 }
@@ -8,6 +8,7 @@ use super::{
     protein_wallet::{self, ProteinWallet},
 };
 
+#[derive(Clone)]
 pub struct Player {
     id: u8,
     wallet: ProteinWallet,
@@ -86,6 +87,10 @@ impl Player {
     pub fn get_wallet(&self) -> ProteinWallet {
         self.wallet
     }
+
+    pub fn set_wallet(&mut self, wallet: ProteinWallet) {
+        self.wallet = wallet;
+    }
 }
 
 #[cfg(test)]
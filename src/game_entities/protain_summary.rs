@@ -8,6 +8,12 @@ pub struct ProteinSummary {
     from_absorb: HashMap<Protein, u32>,
 }
 
+impl Default for ProteinSummary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ProteinSummary {
     pub fn new() -> Self {
         ProteinSummary {
@@ -6,7 +6,10 @@ pub enum Protein {
     D = 0b11,
 }
 
+pub const PROTEINS: [Protein; 4] = [Protein::A, Protein::B, Protein::C, Protein::D];
+
 impl Protein {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Protein> {
         match s {
             "A" => Some(Protein::A),
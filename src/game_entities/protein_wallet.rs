@@ -9,8 +9,11 @@ pub fn new() -> ProteinWallet {
     0
 }
 
+/// Saturates at `u8::MAX`, a larger count would spill into the next protein.
 pub fn add(wallet: &mut ProteinWallet, protein_type: Protein, amount: u32) {
-    *wallet += amount << (protein_type as ProteinWallet * BITS_PROTEIN);
+    let shift = protein_type as ProteinWallet * BITS_PROTEIN;
+    let count = (get(*wallet, protein_type) as u32).saturating_add(amount);
+    *wallet = (*wallet & !(MASK_PROTEIN << shift)) | (count.min(MASK_PROTEIN) << shift);
 }
 
 pub fn remove(wallet: &mut ProteinWallet, protein_type: Protein, amount: u32) {
@@ -22,8 +25,11 @@ pub fn get(wallet: ProteinWallet, protein_type: Protein) -> u8 {
 }
 
 pub fn can_buy_organ(wallet: ProteinWallet, organ_type: OrganType) -> bool {
-    let cost = organ_type.get_cost();
+    can_pay(wallet, organ_type.get_cost())
+}
 
+/// True when `wallet` holds at least `cost` of every protein.
+pub fn can_pay(wallet: ProteinWallet, cost: ProteinWallet) -> bool {
     get(wallet, Protein::A) >= get(cost, Protein::A)
         && get(wallet, Protein::B) >= get(cost, Protein::B)
         && get(wallet, Protein::C) >= get(cost, Protein::C)
@@ -1,56 +1,124 @@
-use std::io;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    time::Instant,
+};
 pub mod actions;
+pub mod config;
+pub mod entites_managers;
 pub mod game_entities;
+pub mod simulation;
 pub mod strategies;
 
+use actions::{action, action_command, actions_coordinator};
+use config::{BotConfig, LogLevel};
+use game_entities::game_state::{self, Entity, GameState, MY_ID, OPPONENT_ID};
+use strategies::{
+    bot_memory::BotMemory, endgame_filler, opponent_model::OpponentModel, registry, self_play,
+    time_manager::TimeManager,
+};
+
 macro_rules! parse_input {
     ($x:expr, $t:ident) => {
         $x.trim().parse::<$t>().unwrap()
     };
 }
 
+fn read_wallet() -> game_entities::protein_wallet::ProteinWallet {
+    let mut input_line = String::new();
+    io::stdin().read_line(&mut input_line).unwrap();
+    let inputs = input_line.split(' ').collect::<Vec<_>>();
+    game_state::wallet_from_counts(
+        parse_input!(inputs[0], u32),
+        parse_input!(inputs[1], u32),
+        parse_input!(inputs[2], u32),
+        parse_input!(inputs[3], u32),
+    )
+}
+
 fn main() {
+    let config = BotConfig::from_env().unwrap_or_else(|error| panic!("{}", error));
+    if config.is_logged(LogLevel::Debug) {
+        eprintln!("{:?}", config);
+    }
+    if config.self_play_games > 0 {
+        let file = File::create(&config.output)
+            .unwrap_or_else(|error| panic!("Cannot create {}: {}", config.output, error));
+        self_play::run(&config.get_self_play_config(), &mut BufWriter::new(file)).unwrap();
+        return;
+    }
+    let mut strategy =
+        registry::create_strategy(&config.strategy, &config.evaluation, config.seed)
+            .unwrap_or_else(|| panic!("Unknown strategy {}", config.strategy));
+
     let mut input_line = String::new();
     io::stdin().read_line(&mut input_line).unwrap();
-    let inputs = input_line.split(" ").collect::<Vec<_>>();
-    let width = parse_input!(inputs[0], i32);
-    let height = parse_input!(inputs[1], i32);
+    let inputs = input_line.split(' ').collect::<Vec<_>>();
+    let width = parse_input!(inputs[0], u8);
+    let height = parse_input!(inputs[1], u8);
+    let mut turn = 0;
+    let mut time = TimeManager::new();
+    time.set_turn_budget(config.turn_budget);
+    let mut memory = BotMemory::new();
     loop {
+        turn += 1;
         let mut input_line = String::new();
         io::stdin().read_line(&mut input_line).unwrap();
+        time.start_turn(turn == 1);
         let entity_count = parse_input!(input_line, i32);
-        for i in 0..entity_count as usize {
+        let mut entities = Vec::with_capacity(entity_count as usize);
+        for _ in 0..entity_count as usize {
             let mut input_line = String::new();
             io::stdin().read_line(&mut input_line).unwrap();
-            let inputs = input_line.split(" ").collect::<Vec<_>>();
-            let x = parse_input!(inputs[0], i32);
-            let y = parse_input!(inputs[1], i32);
-            let _type = inputs[2].trim().to_string();
-            let owner = parse_input!(inputs[3], i32);
-            let organ_id = parse_input!(inputs[4], i32);
-            let organ_dir = inputs[5].trim().to_string();
-            let organ_parent_id = parse_input!(inputs[6], i32);
-            let organ_root_id = parse_input!(inputs[7], i32);
+            entities.push(Entity::from_line(&input_line));
         }
+        let mut wallets = [0; 2];
+        wallets[MY_ID as usize] = read_wallet();
+        wallets[OPPONENT_ID as usize] = read_wallet();
         let mut input_line = String::new();
         io::stdin().read_line(&mut input_line).unwrap();
-        let inputs = input_line.split(" ").collect::<Vec<_>>();
-        let my_a = parse_input!(inputs[0], i32);
-        let my_b = parse_input!(inputs[1], i32);
-        let my_c = parse_input!(inputs[2], i32);
-        let my_d = parse_input!(inputs[3], i32);
-        let mut input_line = String::new();
-        io::stdin().read_line(&mut input_line).unwrap();
-        let inputs = input_line.split(" ").collect::<Vec<_>>();
-        let opp_a = parse_input!(inputs[0], i32);
-        let opp_b = parse_input!(inputs[1], i32);
-        let opp_c = parse_input!(inputs[2], i32);
-        let opp_d = parse_input!(inputs[3], i32);
-        let mut input_line = String::new();
-        io::stdin().read_line(&mut input_line).unwrap();
-        let required_actions_count = parse_input!(input_line, i32);
-        for i in 0..required_actions_count as usize {
-            println!("GROWTH 1 17 8 BASIC N");
+        let required_actions_count = parse_input!(input_line, usize);
+
+        let mut state =
+            GameState::from_entities(width, height, &entities, wallets, required_actions_count);
+        state.turn = turn;
+        memory.record_turn(&state);
+        let opponent_model = OpponentModel::from_history(memory.get_history());
+        if config.is_logged(LogLevel::Info) {
+            if let Some(inferred) = memory.get_last_inference() {
+                eprintln!("{}", inferred);
+            }
+            eprintln!("opponent style: {:?}", opponent_model.classify());
+        }
+        strategy.observe_opponent(&opponent_model);
+        if let Some(inferred) = memory.get_last_inference() {
+            strategy.observe_last_turn(inferred, memory.get_last_actions());
+        }
+        let actions = if endgame_filler::is_separated(&state.grid) {
+            endgame_filler::fill(&state, MY_ID)
+        } else {
+            actions_coordinator::make_consistent(&state, MY_ID, &strategy.play(&state, &time))
+        };
+        let (actions, rejected) = actions_coordinator::secure(&state, MY_ID, &actions);
+        if config.is_logged(LogLevel::Info) {
+            for invalid in rejected {
+                eprintln!(
+                    "Warning: invalid action {} replaced",
+                    action_command::to_command(invalid, &state)
+                );
+            }
+        }
+        memory.record_actions(&actions);
+        if config.is_logged(LogLevel::Debug) {
+            eprintln!("turn {} played in {:?}", turn, time.elapsed());
+        }
+        let flush_start = Instant::now();
+        let mut stdout = io::stdout().lock();
+        for i in 0..required_actions_count {
+            let action = actions.get(i).copied().unwrap_or_else(action::wait);
+            writeln!(stdout, "{}", action_command::to_command(action, &state)).unwrap();
         }
+        stdout.flush().unwrap();
+        time.record_flush(flush_start.elapsed());
     }
 }
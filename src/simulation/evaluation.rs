@@ -0,0 +1,422 @@
+use crate::{
+    entites_managers::{
+        proteins_manager::{ProteinsManager, UNREACHABLE},
+        threat_map::ThreatMap,
+    },
+    game_entities::{cell, game_state::GameState, organ, protein::PROTEINS},
+};
+
+use super::value_network::ValueNetwork;
+
+/// Weight of each feature, a negative weight penalizes it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EvaluationParams {
+    pub organs: f64,
+    /// Protein sources harvested, so proteins gained per turn.
+    pub income: f64,
+    /// Wallet where each protein is worth more when it has few sources.
+    pub wallet: f64,
+    /// Cells reached strictly before the opponent.
+    pub territory: f64,
+    /// Organs the opponent can attack next turn.
+    pub threatened: f64,
+    /// Free protein sources reached strictly before the opponent.
+    pub sources_reached_first: f64,
+    pub roots: f64,
+    /// Embedded value network, an alternative to the hand-written features.
+    pub value_network: f64,
+}
+
+impl Default for EvaluationParams {
+    fn default() -> Self {
+        EvaluationParams {
+            organs: 1.0,
+            income: 2.0,
+            wallet: 0.5,
+            territory: 0.05,
+            threatened: -0.5,
+            sources_reached_first: 0.5,
+            roots: 1.0,
+            value_network: 0.0,
+        }
+    }
+}
+
+impl EvaluationParams {
+    /// Sets the weight of the field called `name`.
+    pub fn set_weight(&mut self, name: &str, value: f64) -> Result<(), String> {
+        let weight = match name {
+            "organs" => &mut self.organs,
+            "income" => &mut self.income,
+            "wallet" => &mut self.wallet,
+            "territory" => &mut self.territory,
+            "threatened" => &mut self.threatened,
+            "sources_reached_first" => &mut self.sources_reached_first,
+            "roots" => &mut self.roots,
+            "value_network" => &mut self.value_network,
+            _ => return Err(format!("Unknown evaluation weight {}", name)),
+        };
+        *weight = value;
+        Ok(())
+    }
+
+    /// Default weights overridden by `name=value` pairs separated by commas or lines.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut params = EvaluationParams::default();
+        for pair in text
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected name=value, got {}", pair))?;
+            let value = value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid value for {}: {}", name, value))?;
+            params.set_weight(name.trim(), value)?;
+        }
+        Ok(params)
+    }
+}
+
+/// Weighted difference of each feature between a player and its opponent.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Evaluation {
+    pub organs: f64,
+    pub income: f64,
+    pub wallet: f64,
+    pub territory: f64,
+    pub threatened: f64,
+    pub sources_reached_first: f64,
+    pub roots: f64,
+    pub value_network: f64,
+}
+
+impl Evaluation {
+    pub fn get_features(&self) -> [(&'static str, f64); 8] {
+        [
+            ("organs", self.organs),
+            ("income", self.income),
+            ("wallet", self.wallet),
+            ("territory", self.territory),
+            ("threatened", self.threatened),
+            ("sources_reached_first", self.sources_reached_first),
+            ("roots", self.roots),
+            ("value_network", self.value_network),
+        ]
+    }
+
+    pub fn get_total(&self) -> f64 {
+        self.get_features().iter().map(|(_, value)| value).sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Features {
+    organs: f64,
+    income: f64,
+    wallet: f64,
+    territory: f64,
+    threatened: f64,
+    sources_reached_first: f64,
+    roots: f64,
+}
+
+/// Position of `owner` in `state`, feature by feature.
+pub fn evaluate(state: &GameState, owner: u8, params: &EvaluationParams) -> Evaluation {
+    let proteins_manager = ProteinsManager::new(&state.grid);
+    let mut scarcities = [0.0; 4];
+    for (scarcity, &protein) in scarcities.iter_mut().zip(PROTEINS.iter()) {
+        let sources = proteins_manager
+            .get_reports()
+            .iter()
+            .filter(|report| report.protein == protein)
+            .count();
+        *scarcity = 1.0 / (1.0 + sources as f64);
+    }
+    let mine = compute_features(state, owner, &proteins_manager, &scarcities);
+    let theirs = compute_features(state, 1 - owner, &proteins_manager, &scarcities);
+    Evaluation {
+        organs: params.organs * (mine.organs - theirs.organs),
+        income: params.income * (mine.income - theirs.income),
+        wallet: params.wallet * (mine.wallet - theirs.wallet),
+        territory: params.territory * (mine.territory - theirs.territory),
+        threatened: params.threatened * (mine.threatened - theirs.threatened),
+        sources_reached_first: params.sources_reached_first
+            * (mine.sources_reached_first - theirs.sources_reached_first),
+        roots: params.roots * (mine.roots - theirs.roots),
+        // the network is skipped when unused, the searches evaluate many states
+        value_network: if params.value_network == 0.0 {
+            0.0
+        } else {
+            let network = ValueNetwork::embedded();
+            params.value_network
+                * (network.get_value(state, owner) - network.get_value(state, 1 - owner))
+        },
+    }
+}
+
+pub fn get_score(state: &GameState, owner: u8, params: &EvaluationParams) -> f64 {
+    evaluate(state, owner, params).get_total()
+}
+
+/// Organs, harvested sources, wallet and roots only, cheap enough to rank every child of a
+/// search before the full evaluation of the ones kept.
+pub fn get_quick_score(state: &GameState, owner: u8, params: &EvaluationParams) -> f64 {
+    let grid = &state.grid;
+    let scarcities =
+        PROTEINS.map(|protein| 1.0 / (1.0 + grid.iter_protein_sources_of(protein).count() as f64));
+    let score = |player_id: u8| {
+        let player = &state.players[player_id as usize];
+        let wallet: f64 = PROTEINS
+            .iter()
+            .zip(scarcities.iter())
+            .map(|(&protein, scarcity)| player.get_nb_protein(protein) as f64 * scarcity)
+            .sum();
+        params.organs * player.get_score() as f64
+            + params.income * count_harvested_sources(state, player_id) as f64
+            + params.wallet * wallet
+            + params.roots * player.get_roots().len() as f64
+    };
+    score(owner) - score(1 - owner)
+}
+
+fn count_harvested_sources(state: &GameState, owner: u8) -> usize {
+    let grid = &state.grid;
+    grid.iter_protein_sources()
+        .filter(|source| {
+            grid.get_adjacent_coords(source.coord)
+                .into_iter()
+                .any(|adjacent| {
+                    let cell = grid.get_cell_from_coord(adjacent);
+                    cell::is_owned_by(cell, owner)
+                        && organ::is_harvester(cell::get_organ(cell).unwrap())
+                        && organ::is_faced_to(
+                            cell::get_organ(cell).unwrap(),
+                            adjacent,
+                            source.coord,
+                        )
+                })
+        })
+        .count()
+}
+
+fn compute_features(
+    state: &GameState,
+    owner: u8,
+    proteins_manager: &ProteinsManager,
+    scarcities: &[f64; 4],
+) -> Features {
+    let player = &state.players[owner as usize];
+    let opponent = 1 - owner;
+    let threat_map = ThreatMap::new(&state.grid, &state.players[opponent as usize]);
+    let grid = &state.grid;
+    let territory = grid
+        .iter_coords()
+        .filter(|&coord| cell::is_growable(grid.get_cell_from_coord(coord)))
+        .filter(|&coord| {
+            let distance = proteins_manager.get_distance(grid, owner, coord);
+            distance != UNREACHABLE
+                && distance < proteins_manager.get_distance(grid, opponent, coord)
+        })
+        .count();
+    Features {
+        organs: player.get_score() as f64,
+        income: proteins_manager
+            .get_reports()
+            .iter()
+            .filter(|report| report.harvested_by[owner as usize])
+            .count() as f64,
+        wallet: PROTEINS
+            .iter()
+            .zip(scarcities.iter())
+            .map(|(&protein, scarcity)| player.get_nb_protein(protein) as f64 * scarcity)
+            .sum(),
+        territory: territory as f64,
+        threatened: grid
+            .iter_organs_of_owner(owner)
+            .filter(|organ| threat_map.is_attackable(organ.coord))
+            .count() as f64,
+        sources_reached_first: proteins_manager.get_contested_sources_won_by(owner).len() as f64,
+        roots: player.get_roots().len() as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::{
+        coord,
+        game_state::{wallet_from_counts, Entity, MY_ID, OPPONENT_ID},
+        organ,
+        organ_direction::OrganDirection,
+        organ_type::OrganType,
+    };
+
+    use super::*;
+
+    fn build_state() -> GameState {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "1 1 HARVESTER 1 2 E 1 1",
+            "9 1 ROOT 0 3 N 0 3",
+            "2 1 A -1 0 X 0 0",
+            "6 1 B -1 0 X 0 0",
+            "5 2 B -1 0 X 0 0",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(
+            10,
+            3,
+            &entities,
+            [
+                wallet_from_counts(0, 0, 0, 0),
+                wallet_from_counts(0, 3, 0, 0),
+            ],
+            1,
+        )
+    }
+
+    #[test]
+    fn test_features() {
+        let state = build_state();
+        let evaluation = evaluate(&state, MY_ID, &EvaluationParams::default());
+
+        assert_eq!(evaluation.organs, 1.0);
+        assert_eq!(evaluation.income, 2.0);
+        assert_eq!(evaluation.wallet, 0.5 * 3.0 / 3.0);
+        assert_eq!(evaluation.roots, 0.0);
+        assert_eq!(evaluation.threatened, 0.0);
+        // the B sources are reached first by the opponent or at the same time
+        assert_eq!(evaluation.sources_reached_first, -0.5);
+        assert!(evaluation.territory > 0.0);
+    }
+
+    #[test]
+    fn test_quick_score_keeps_the_cheap_features() {
+        let state = build_state();
+        let params = EvaluationParams::default();
+        let evaluation = evaluate(&state, MY_ID, &params);
+
+        assert_eq!(
+            get_quick_score(&state, MY_ID, &params),
+            evaluation.organs + evaluation.income + evaluation.wallet + evaluation.roots
+        );
+        assert_eq!(
+            get_quick_score(&state, OPPONENT_ID, &params),
+            -get_quick_score(&state, MY_ID, &params)
+        );
+    }
+
+    #[test]
+    fn test_total_is_the_sum_of_features() {
+        let state = build_state();
+        let evaluation = evaluate(&state, MY_ID, &EvaluationParams::default());
+        let sum: f64 = evaluation
+            .get_features()
+            .iter()
+            .map(|(_, value)| value)
+            .sum();
+
+        assert_eq!(evaluation.get_total(), sum);
+        assert_eq!(get_score(&state, MY_ID, &EvaluationParams::default()), sum);
+    }
+
+    #[test]
+    fn test_evaluation_is_antisymmetric() {
+        let state = build_state();
+        let params = EvaluationParams::default();
+
+        assert_eq!(
+            get_score(&state, MY_ID, &params),
+            -get_score(&state, OPPONENT_ID, &params)
+        );
+    }
+
+    #[test]
+    fn test_weights_are_applied() {
+        let state = build_state();
+        let params = EvaluationParams {
+            organs: 3.0,
+            income: 0.0,
+            ..EvaluationParams::default()
+        };
+        let evaluation = evaluate(&state, MY_ID, &params);
+
+        assert_eq!(evaluation.organs, 3.0);
+        assert_eq!(evaluation.income, 0.0);
+    }
+
+    #[test]
+    fn test_threatened_organs() {
+        let mut state = build_state();
+        let opponent_root = coord::new(9, 1);
+        for x in [8, 7] {
+            state.add_organ(
+                coord::new(x, 1),
+                organ::new(
+                    OPPONENT_ID,
+                    OrganType::Basic,
+                    OrganDirection::West,
+                    opponent_root,
+                ),
+            );
+        }
+        state.players[OPPONENT_ID as usize].set_wallet(wallet_from_counts(0, 1, 0, 1));
+        assert_eq!(
+            evaluate(&state, MY_ID, &EvaluationParams::default()).threatened,
+            0.0
+        );
+
+        // the opponent can grow a tentacle on (7, 0) facing the new root
+        let new_root = coord::new(6, 0);
+        state.add_organ(
+            new_root,
+            organ::new(MY_ID, OrganType::Root, OrganDirection::North, new_root),
+        );
+        let evaluation = evaluate(&state, MY_ID, &EvaluationParams::default());
+        assert_eq!(evaluation.threatened, -0.5);
+        assert_eq!(evaluation.roots, 1.0);
+    }
+
+    #[test]
+    fn test_value_network_replaces_the_features() {
+        let state = build_state();
+        let params = EvaluationParams::parse(
+            "organs=0,income=0,wallet=0,territory=0,threatened=0,\
+             sources_reached_first=0,roots=0,value_network=1",
+        )
+        .unwrap();
+        let network = ValueNetwork::embedded();
+
+        assert_eq!(
+            get_score(&state, MY_ID, &params),
+            network.get_value(&state, MY_ID) - network.get_value(&state, OPPONENT_ID)
+        );
+        assert_eq!(
+            evaluate(&state, MY_ID, &EvaluationParams::default()).value_network,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        let params = EvaluationParams::parse("income=3, roots = 2.5\nthreatened=-1").unwrap();
+
+        assert_eq!(
+            params,
+            EvaluationParams {
+                income: 3.0,
+                roots: 2.5,
+                threatened: -1.0,
+                ..EvaluationParams::default()
+            }
+        );
+        assert!(EvaluationParams::parse("speed=1").is_err());
+        assert!(EvaluationParams::parse("income").is_err());
+        assert!(EvaluationParams::parse("income=fast").is_err());
+    }
+}
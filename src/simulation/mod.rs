@@ -0,0 +1,3 @@
+pub mod evaluation;
+pub mod turn_simulator;
+pub mod value_network;
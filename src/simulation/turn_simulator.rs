@@ -0,0 +1,411 @@
+use std::collections::HashSet;
+
+use crate::{
+    actions::{
+        action::{self, Action},
+        action_type::ActionType,
+        actions_finder,
+    },
+    entites_managers::proteins_manager::ABSORB_GAIN,
+    game_entities::{
+        cell,
+        coord::{self, Coord},
+        game_state::GameState,
+        grid::Grid,
+        organ::{self, Organ},
+        organ_direction::OrganDirection,
+        organ_type::OrganType,
+        protein_wallet,
+    },
+};
+
+pub const MAX_TURNS: u32 = 100;
+
+/// Plays one turn for both players at once, `actions` being indexed by player id.
+/// Invalid or unaffordable actions are ignored, as the referee turns them into WAIT.
+pub fn simulate_turn(state: &mut GameState, actions: &[Vec<Action>; 2]) {
+    let mut growths: [Vec<(Coord, Organ)>; 2] = [Vec::new(), Vec::new()];
+    for (owner, owner_actions) in actions.iter().enumerate() {
+        growths[owner] = pay_valid_growths(state, owner as u8, owner_actions);
+    }
+    let contested: HashSet<Coord> = growths[0]
+        .iter()
+        .map(|&(coord, _)| coord)
+        .filter(|coord| growths[1].iter().any(|(other, _)| other == coord))
+        .collect();
+    for &coord in contested.iter() {
+        state
+            .grid
+            .set_cell(coord::x(coord), coord::y(coord), cell::new_wall());
+    }
+    for (coord, organ) in growths.into_iter().flatten() {
+        if contested.contains(&coord) {
+            continue;
+        }
+        if let Some(protein) = cell::get_protein(state.grid.get_cell_from_coord(coord)) {
+            state.players[organ::get_owner(organ) as usize].add_protein(protein, ABSORB_GAIN);
+        }
+        state.add_organ(coord, organ);
+    }
+    apply_tentacle_attacks(state);
+    apply_harvest(state);
+    state.turn += 1;
+}
+
+/// Organ the referee would place for `action` of `owner`, None when it cannot be played.
+/// The cost is not checked.
+pub fn get_placed_organ(grid: &Grid, owner: u8, action: Action) -> Option<Organ> {
+    let target = action::get_coord_target(action);
+    let organ = match action::get_type(action) {
+        ActionType::Wait => return None,
+        ActionType::Growth => organ::new(
+            owner,
+            action::get_organ_type(action),
+            action::get_direction(action),
+            action::get_coord_source(action),
+        ),
+        ActionType::Sporer => {
+            actions_finder::find_sporer(grid, owner, action)?;
+            organ::new(owner, OrganType::Root, OrganDirection::North, target)
+        }
+    };
+    let is_placeable = if organ::is_root(organ) {
+        action::get_type(action) == ActionType::Sporer
+            && grid.can_add_organ_without_root_coord(target, organ)
+    } else {
+        grid.can_add_organ_with_root_coord(target, organ)
+    };
+    if is_placeable {
+        Some(organ)
+    } else {
+        None
+    }
+}
+
+fn pay_valid_growths(state: &mut GameState, owner: u8, actions: &[Action]) -> Vec<(Coord, Organ)> {
+    let mut wallet = state.players[owner as usize].get_wallet();
+    let mut growths: Vec<(Coord, Organ)> = Vec::new();
+    for &action in actions {
+        let target = action::get_coord_target(action);
+        let organ = match get_placed_organ(&state.grid, owner, action) {
+            Some(organ) => organ,
+            None => continue,
+        };
+        let organ_type = organ::get_type(organ);
+        if protein_wallet::can_buy_organ(wallet, organ_type)
+            && growths.iter().all(|&(coord, _)| coord != target)
+        {
+            protein_wallet::buy_organ(&mut wallet, organ_type);
+            growths.push((target, organ));
+        }
+    }
+    state.players[owner as usize].set_wallet(wallet);
+    growths
+}
+
+fn apply_tentacle_attacks(state: &mut GameState) {
+    let mut victims = Vec::new();
+    for tentacle in state.grid.iter_organs() {
+        if tentacle.organ_type != OrganType::Tentacle {
+            continue;
+        }
+        if let Some(target) = state
+            .grid
+            .get_next_coord(tentacle.coord, tentacle.direction)
+        {
+            if cell::is_owned_by(state.grid.get_cell_from_coord(target), 1 - tentacle.owner) {
+                victims.push(target);
+            }
+        }
+    }
+    for victim in victims {
+        if cell::is_organ(state.grid.get_cell_from_coord(victim)) {
+            state.remove_organ(victim);
+        }
+    }
+}
+
+fn apply_harvest(state: &mut GameState) {
+    for owner in 0..2u8 {
+        let harvested: HashSet<Coord> = state
+            .grid
+            .iter_organs_of_owner(owner)
+            .filter(|organ| organ.organ_type == OrganType::Harvester)
+            .filter_map(|organ| state.grid.get_next_coord(organ.coord, organ.direction))
+            .filter(|&target| cell::is_protein(state.grid.get_cell_from_coord(target)))
+            .collect();
+        for target in harvested {
+            let protein = cell::get_protein(state.grid.get_cell_from_coord(target)).unwrap();
+            state.players[owner as usize].add_protein(protein, 1);
+        }
+    }
+}
+
+pub fn is_game_over(state: &GameState) -> bool {
+    state.turn >= MAX_TURNS || state.players.iter().any(|player| player.get_score() == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::{
+        game_state::{wallet_from_counts, Entity, MY_ID, OPPONENT_ID},
+        protein::Protein,
+    };
+
+    use super::*;
+
+    fn build_state() -> GameState {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "6 1 ROOT 0 2 N 0 2",
+            "1 0 A -1 0 X 0 0",
+            "3 0 WALL -1 0 X 0 0",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(
+            7,
+            3,
+            &entities,
+            [
+                wallet_from_counts(2, 2, 2, 2),
+                wallet_from_counts(2, 2, 2, 2),
+            ],
+            1,
+        )
+    }
+
+    fn wait() -> Vec<Action> {
+        vec![action::wait()]
+    }
+
+    #[test]
+    fn test_growth_pays_and_places_organ() {
+        let mut state = build_state();
+        let root = coord::new(0, 1);
+        let growth = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            coord::new(1, 1),
+            root,
+        );
+
+        simulate_turn(&mut state, &[wait(), vec![growth]]);
+
+        assert_eq!(state.get_me().get_score(), 2);
+        assert_eq!(state.get_me().get_nb_protein(Protein::A), 1);
+        assert_eq!(state.turn, 1);
+        assert!(cell::is_owned_by(state.grid.get_cell(1, 1), MY_ID));
+    }
+
+    #[test]
+    fn test_growth_on_protein_absorbs_it() {
+        let mut state = build_state();
+        let root = coord::new(0, 1);
+        let basic = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            coord::new(0, 0),
+            root,
+        );
+        let on_protein = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            coord::new(1, 0),
+            root,
+        );
+
+        simulate_turn(&mut state, &[wait(), vec![basic]]);
+        simulate_turn(&mut state, &[wait(), vec![on_protein]]);
+
+        assert_eq!(state.get_me().get_score(), 3);
+        assert_eq!(state.get_me().get_nb_protein(Protein::A), 3);
+    }
+
+    #[test]
+    fn test_invalid_and_unaffordable_actions_are_ignored() {
+        let mut state = build_state();
+        let root = coord::new(0, 1);
+        let far = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            coord::new(5, 2),
+            root,
+        );
+        let first = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            coord::new(1, 1),
+            root,
+        );
+        let second = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            coord::new(0, 2),
+            root,
+        );
+        let third = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            coord::new(0, 0),
+            root,
+        );
+
+        simulate_turn(&mut state, &[wait(), vec![far, first, second, third]]);
+
+        assert_eq!(state.get_me().get_score(), 3);
+        assert_eq!(state.get_me().get_nb_protein(Protein::A), 0);
+        assert!(cell::is_empty(state.grid.get_cell(0, 0)));
+    }
+
+    fn add_line_organs(state: &mut GameState) {
+        let my_root = coord::new(0, 1);
+        let opp_root = coord::new(6, 1);
+        for x in [1, 2] {
+            state.add_organ(
+                coord::new(x, 1),
+                organ::new(MY_ID, OrganType::Basic, OrganDirection::North, my_root),
+            );
+        }
+        for x in [5, 4] {
+            state.add_organ(
+                coord::new(x, 1),
+                organ::new(
+                    OPPONENT_ID,
+                    OrganType::Basic,
+                    OrganDirection::North,
+                    opp_root,
+                ),
+            );
+        }
+    }
+
+    #[test]
+    fn test_same_target_spawns_wall() {
+        let mut state = build_state();
+        add_line_organs(&mut state);
+        let target = coord::new(3, 1);
+        let mine = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            target,
+            coord::new(0, 1),
+        );
+        let theirs = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            target,
+            coord::new(6, 1),
+        );
+
+        simulate_turn(&mut state, &[vec![theirs], vec![mine]]);
+
+        assert!(cell::is_obstacle(state.grid.get_cell_from_coord(target)));
+        assert_eq!(state.get_me().get_nb_protein(Protein::A), 1);
+        assert_eq!(state.get_opponent().get_nb_protein(Protein::A), 1);
+        assert_eq!(state.get_me().get_score(), 3);
+    }
+
+    #[test]
+    fn test_tentacle_kills_subtree() {
+        let mut state = build_state();
+        add_line_organs(&mut state);
+        // grows from (2, 1), so it dies with it
+        state.add_organ(
+            coord::new(2, 2),
+            organ::new(
+                MY_ID,
+                OrganType::Basic,
+                OrganDirection::North,
+                coord::new(0, 1),
+            ),
+        );
+        let tentacle = action::growth(
+            OrganType::Tentacle,
+            OrganDirection::West,
+            coord::new(3, 1),
+            coord::new(6, 1),
+        );
+
+        simulate_turn(&mut state, &[vec![tentacle], wait()]);
+
+        assert_eq!(state.get_me().get_score(), 2);
+        assert!(cell::is_empty(state.grid.get_cell(2, 1)));
+        assert!(cell::is_empty(state.grid.get_cell(2, 2)));
+        assert!(cell::is_owned_by(state.grid.get_cell(1, 1), MY_ID));
+        assert_eq!(state.get_opponent().get_score(), 4);
+    }
+
+    #[test]
+    fn test_harvest_once_per_source() {
+        let mut state = build_state();
+        let root = coord::new(0, 1);
+        state.add_organ(
+            coord::new(0, 0),
+            organ::new(MY_ID, OrganType::Harvester, OrganDirection::East, root),
+        );
+        state.add_organ(
+            coord::new(1, 1),
+            organ::new(MY_ID, OrganType::Harvester, OrganDirection::North, root),
+        );
+
+        simulate_turn(&mut state, &[wait(), wait()]);
+
+        assert_eq!(state.get_me().get_nb_protein(Protein::A), 3);
+    }
+
+    #[test]
+    fn test_spore_creates_new_root() {
+        let mut state = build_state();
+        let root = coord::new(0, 1);
+        state.add_organ(
+            coord::new(0, 2),
+            organ::new(MY_ID, OrganType::Sporer, OrganDirection::East, root),
+        );
+        let spore = action::sporer(OrganDirection::East, coord::new(4, 2), root);
+
+        simulate_turn(&mut state, &[wait(), vec![spore]]);
+
+        assert_eq!(state.get_roots_in_order(MY_ID).len(), 2);
+        assert_eq!(state.get_me().get_nb_protein(Protein::D), 1);
+        let new_root = cell::get_organ(state.grid.get_cell(4, 2)).unwrap();
+        assert_eq!(organ::get_root_coord(new_root), coord::new(4, 2));
+    }
+
+    #[test]
+    fn test_get_placed_organ() {
+        let state = build_state();
+        let root = coord::new(0, 1);
+        let growth = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            coord::new(1, 1),
+            root,
+        );
+        let far = action::growth(
+            OrganType::Basic,
+            OrganDirection::North,
+            coord::new(4, 1),
+            root,
+        );
+        let spore = action::sporer(OrganDirection::East, coord::new(4, 1), root);
+
+        assert!(get_placed_organ(&state.grid, MY_ID, growth).is_some());
+        assert!(get_placed_organ(&state.grid, MY_ID, far).is_none());
+        assert!(get_placed_organ(&state.grid, MY_ID, spore).is_none());
+        assert!(get_placed_organ(&state.grid, MY_ID, action::wait()).is_none());
+    }
+
+    #[test]
+    fn test_is_game_over() {
+        let mut state = build_state();
+
+        assert!(!is_game_over(&state));
+        state.turn = MAX_TURNS;
+        assert!(is_game_over(&state));
+        state.turn = 0;
+        state.remove_organ(coord::new(6, 1));
+        assert!(is_game_over(&state));
+    }
+}
@@ -0,0 +1,197 @@
+use std::sync::OnceLock;
+
+use crate::game_entities::{cell_content::CellContent, game_state::GameState, protein::PROTEINS};
+
+/// Walls, the 4 source planes, the 5 organ planes of each player and both wallets.
+pub const NB_INPUTS: usize = 23;
+
+pub const NB_HIDDEN: usize = 16;
+
+pub const NB_PARAMS: usize = NB_HIDDEN * (NB_INPUTS + 1) + NB_HIDDEN + 1;
+
+/// Counts are divided by it to keep the inputs around 1.
+pub const INPUT_SCALE: f64 = 10.0;
+
+const EMBEDDED_WEIGHTS: &str = include_str!("value_network_weights.txt");
+
+static EMBEDDED: OnceLock<ValueNetwork> = OnceLock::new();
+
+/// One hidden ReLU layer scoring a state for a player from its feature planes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueNetwork {
+    hidden_weights: [[f64; NB_INPUTS]; NB_HIDDEN],
+    hidden_biases: [f64; NB_HIDDEN],
+    output_weights: [f64; NB_HIDDEN],
+    output_bias: f64,
+}
+
+impl ValueNetwork {
+    /// Network of `value_network_weights.txt`, embedded at compile time.
+    pub fn embedded() -> &'static ValueNetwork {
+        EMBEDDED.get_or_init(|| {
+            ValueNetwork::parse(EMBEDDED_WEIGHTS).unwrap_or_else(|error| panic!("{}", error))
+        })
+    }
+
+    /// `NB_PARAMS` numbers separated by blanks, hidden unit by hidden unit with the bias
+    /// after the weights, then the output layer. Lines starting with # are comments.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let params = text
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(str::split_whitespace)
+            .map(|value| {
+                value
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid network weight {}", value))
+            })
+            .collect::<Result<Vec<f64>, String>>()?;
+        if params.len() != NB_PARAMS {
+            return Err(format!(
+                "Expected {} network weights, got {}",
+                NB_PARAMS,
+                params.len()
+            ));
+        }
+        let mut network = ValueNetwork {
+            hidden_weights: [[0.0; NB_INPUTS]; NB_HIDDEN],
+            hidden_biases: [0.0; NB_HIDDEN],
+            output_weights: [0.0; NB_HIDDEN],
+            output_bias: 0.0,
+        };
+        let mut values = params.into_iter();
+        for (weights, bias) in network
+            .hidden_weights
+            .iter_mut()
+            .zip(network.hidden_biases.iter_mut())
+        {
+            weights
+                .iter_mut()
+                .for_each(|weight| *weight = values.next().unwrap());
+            *bias = values.next().unwrap();
+        }
+        for weight in network.output_weights.iter_mut() {
+            *weight = values.next().unwrap();
+        }
+        network.output_bias = values.next().unwrap();
+        Ok(network)
+    }
+
+    pub fn forward(&self, inputs: &[f64; NB_INPUTS]) -> f64 {
+        self.hidden_weights
+            .iter()
+            .zip(self.hidden_biases.iter())
+            .zip(self.output_weights.iter())
+            .map(|((weights, bias), output_weight)| {
+                let activation: f64 = weights
+                    .iter()
+                    .zip(inputs.iter())
+                    .map(|(weight, input)| weight * input)
+                    .sum::<f64>()
+                    + bias;
+                output_weight * activation.max(0.0)
+            })
+            .sum::<f64>()
+            + self.output_bias
+    }
+
+    pub fn get_value(&self, state: &GameState, owner: u8) -> f64 {
+        self.forward(&get_inputs(state, owner))
+    }
+}
+
+/// Size of each feature plane, the planes of `owner` before the opponent ones.
+pub fn get_inputs(state: &GameState, owner: u8) -> [f64; NB_INPUTS] {
+    let mut counts = [0u32; NB_INPUTS];
+    for (_, content) in state.grid.iter_cells() {
+        match content {
+            CellContent::Empty => {}
+            CellContent::Wall => counts[0] += 1,
+            CellContent::Protein(source) => counts[1 + source.protein as usize] += 1,
+            CellContent::Organ(organ) => {
+                let side = if organ.owner == owner { 0 } else { 1 };
+                counts[5 + 5 * side + organ.organ_type.get_index() as usize] += 1;
+            }
+        }
+    }
+    for (side, player) in [owner, 1 - owner].into_iter().enumerate() {
+        for protein in PROTEINS {
+            counts[15 + 4 * side + protein as usize] =
+                state.players[player as usize].get_nb_protein(protein);
+        }
+    }
+    counts.map(|count| count as f64 / INPUT_SCALE)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::game_state::{wallet_from_counts, Entity, MY_ID, OPPONENT_ID};
+
+    use super::*;
+
+    fn build_state() -> GameState {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "1 1 HARVESTER 1 2 E 1 1",
+            "2 1 B -1 0 X 0 0",
+            "3 0 WALL -1 0 X 0 0",
+            "5 1 ROOT 0 3 N 0 3",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(
+            6,
+            3,
+            &entities,
+            [
+                wallet_from_counts(0, 0, 0, 0),
+                wallet_from_counts(2, 0, 0, 1),
+            ],
+            1,
+        )
+    }
+
+    #[test]
+    fn test_embedded_weights_are_valid() {
+        assert!(ValueNetwork::parse(EMBEDDED_WEIGHTS).is_ok());
+    }
+
+    #[test]
+    fn test_parse_checks_the_size() {
+        assert!(ValueNetwork::parse("1 2 3").is_err());
+        assert!(ValueNetwork::parse(&"x ".repeat(NB_PARAMS)).is_err());
+        let network = ValueNetwork::parse(&"0 ".repeat(NB_PARAMS)).unwrap();
+        assert_eq!(network.forward(&[1.0; NB_INPUTS]), 0.0);
+    }
+
+    #[test]
+    fn test_inputs() {
+        let state = build_state();
+        let inputs = get_inputs(&state, MY_ID);
+
+        assert_eq!(inputs[0], 0.1);
+        // one B source, our ROOT and HARVESTER, their ROOT, our A and D
+        assert_eq!(inputs[2], 0.1);
+        assert_eq!(inputs[5], 0.1);
+        assert_eq!(inputs[7], 0.1);
+        assert_eq!(inputs[10], 0.1);
+        assert_eq!(inputs[15], 0.2);
+        assert_eq!(inputs[18], 0.1);
+        assert!((inputs.iter().sum::<f64>() - 0.8).abs() < 1e-9);
+        assert_eq!(get_inputs(&state, OPPONENT_ID)[12], 0.1);
+    }
+
+    #[test]
+    fn test_embedded_network_counts_the_material() {
+        let state = build_state();
+        let network = ValueNetwork::embedded();
+
+        // one organ, a harvester and 3 proteins ahead
+        assert!((network.get_value(&state, MY_ID) - (1.0 + 2.0 + 0.75)).abs() < 1e-9);
+        assert_eq!(
+            network.get_value(&state, OPPONENT_ID),
+            -network.get_value(&state, MY_ID)
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{
+    actions::{
+        action::{self, Action},
+        actions_finder::DIRECTIONS,
+    },
+    game_entities::{
+        cell, coord::Coord, grid::Grid, organ, organ_direction::OrganDirection,
+        organ_type::OrganType, player::Player,
+    },
+};
+
+use super::defense_planner::HARVESTER_VALUE;
+
+/// A root is worth a whole organism for the opponent, on top of its subtree.
+pub const ROOT_VALUE: f64 = 5.0;
+
+/// TENTACLE facing an enemy organ, valued by the subtree its kill removes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttackProposal {
+    pub action: Action,
+    pub value: f64,
+}
+
+/// Every TENTACLE of the organism rooted at `root_coord` facing an enemy organ and safe
+/// from `opponent`, best proposal first.
+pub fn find_attacks(
+    grid: &Grid,
+    player: &Player,
+    opponent: &Player,
+    root_coord: Coord,
+) -> Vec<AttackProposal> {
+    let owner = player.get_id();
+    let mut attacks = Vec::new();
+    for coord in grid.iter_root_frontier(owner, root_coord) {
+        for direction in DIRECTIONS {
+            let target = match grid.get_next_coord(coord, direction) {
+                Some(target) if cell::is_owned_by(grid.get_cell_from_coord(target), 1 - owner) => {
+                    target
+                }
+                _ => continue,
+            };
+            if is_safe(grid, opponent, coord, target) {
+                attacks.push(AttackProposal {
+                    action: action::growth(OrganType::Tentacle, direction, coord, root_coord),
+                    value: get_kill_value(grid, target),
+                });
+            }
+        }
+    }
+    attacks.sort_by(|a, b| b.value.total_cmp(&a.value));
+    attacks
+}
+
+/// Value of the enemy organs dying with the organ on `target`.
+pub fn get_kill_value(grid: &Grid, target: Coord) -> f64 {
+    grid.get_subtree(target)
+        .into_iter()
+        .map(
+            |coord| match cell::get_organ(grid.get_cell_from_coord(coord)) {
+                Some(organ) if organ::get_type(organ) == OrganType::Root => ROOT_VALUE,
+                Some(organ) if organ::is_harvester(organ) => HARVESTER_VALUE,
+                Some(_) => 1.0,
+                None => 0.0,
+            },
+        )
+        .sum()
+}
+
+/// True when `opponent` cannot grow a tentacle facing `coord` in the same turn,
+/// because it cannot afford one or has no free cell next to `coord` to grow it on.
+pub fn is_safe(grid: &Grid, opponent: &Player, coord: Coord, target: Coord) -> bool {
+    if !opponent.can_buy(OrganType::Tentacle) {
+        return true;
+    }
+    let enemy_organ = organ::new(
+        opponent.get_id(),
+        OrganType::Tentacle,
+        OrganDirection::North,
+        0,
+    );
+    grid.get_adjacent_coords(coord)
+        .into_iter()
+        .filter(|&adjacent| adjacent != target)
+        .all(|adjacent| !grid.can_add_organ_without_root_coord(adjacent, enemy_organ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::{
+        coord,
+        game_state::{wallet_from_counts, Entity, GameState, OPPONENT_ID},
+    };
+
+    use super::*;
+
+    fn build_state() -> GameState {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "1 1 BASIC 1 2 N 1 1",
+            "1 0 BASIC 1 3 N 1 1",
+            "4 1 BASIC 0 4 N 6 6",
+            "3 1 HARVESTER 0 5 W 4 6",
+            "4 0 ROOT 0 6 N 0 6",
+            "3 0 WALL -1 0 X 0 0",
+            "3 2 WALL -1 0 X 0 0",
+            "2 0 WALL -1 0 X 0 0",
+            "2 2 WALL -1 0 X 0 0",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(
+            6,
+            3,
+            &entities,
+            [
+                wallet_from_counts(0, 0, 0, 0),
+                wallet_from_counts(0, 1, 0, 1),
+            ],
+            1,
+        )
+    }
+
+    #[test]
+    fn test_kill_value_weights_harvesters_and_roots() {
+        let state = build_state();
+
+        assert_eq!(
+            get_kill_value(&state.grid, coord::new(3, 1)),
+            HARVESTER_VALUE
+        );
+        assert_eq!(
+            get_kill_value(&state.grid, coord::new(4, 1)),
+            1.0 + HARVESTER_VALUE
+        );
+        assert_eq!(
+            get_kill_value(&state.grid, coord::new(4, 0)),
+            ROOT_VALUE + 1.0 + HARVESTER_VALUE
+        );
+    }
+
+    #[test]
+    fn test_find_attacks_in_a_corridor() {
+        let state = build_state();
+        let attacks = find_attacks(
+            &state.grid,
+            state.get_me(),
+            state.get_opponent(),
+            coord::new(0, 1),
+        );
+
+        // (2, 1) is walled on both sides, the opponent cannot answer there
+        assert_eq!(attacks.len(), 1);
+        assert_eq!(
+            attacks[0].action,
+            action::growth(
+                OrganType::Tentacle,
+                OrganDirection::East,
+                coord::new(2, 1),
+                coord::new(0, 1)
+            )
+        );
+        assert_eq!(attacks[0].value, HARVESTER_VALUE);
+    }
+
+    #[test]
+    fn test_unsafe_when_the_opponent_can_answer() {
+        let mut state = build_state();
+        state.players[OPPONENT_ID as usize].set_wallet(wallet_from_counts(0, 1, 0, 1));
+        state.grid.set_cell(2, 2, cell::new(false, None, None));
+        state.grid.set_cell(3, 2, cell::new(false, None, None));
+        state.add_organ(
+            coord::new(3, 2),
+            organ::new(
+                OPPONENT_ID,
+                OrganType::Basic,
+                OrganDirection::South,
+                coord::new(4, 0),
+            ),
+        );
+
+        // the opponent can grow a tentacle on (2, 2) facing (2, 1)
+        assert!(!is_safe(
+            &state.grid,
+            state.get_opponent(),
+            coord::new(2, 1),
+            coord::new(3, 1)
+        ));
+        assert!(find_attacks(
+            &state.grid,
+            state.get_me(),
+            state.get_opponent(),
+            coord::new(0, 1)
+        )
+        .is_empty());
+        // but not without the proteins of a tentacle
+        state.players[OPPONENT_ID as usize].set_wallet(wallet_from_counts(1, 1, 1, 0));
+        assert!(is_safe(
+            &state.grid,
+            state.get_opponent(),
+            coord::new(2, 1),
+            coord::new(3, 1)
+        ));
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::HashSet;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    actions::{
+        action::{self, Action},
+        actions_finder,
+    },
+    game_entities::game_state::{GameState, MY_ID},
+    simulation::{
+        evaluation::{self, EvaluationParams},
+        turn_simulator,
+    },
+};
+
+use super::{
+    opponent_model::{OpponentModel, MIN_OBSERVED_TURNS},
+    play,
+    strategy::Strategy,
+    time_manager::TimeManager,
+};
+
+pub const NAME: &str = "beam";
+
+/// What the opponent is assumed to play while we search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpponentPolicy {
+    Wait,
+    Greedy,
+    /// Sampled from the opponent model, greedy until it has observed enough turns.
+    Model,
+}
+
+#[derive(Debug, Clone)]
+pub struct BeamSearchConfig {
+    /// States kept per depth.
+    pub width: usize,
+    pub depth: usize,
+    pub opponent_policy: OpponentPolicy,
+    pub evaluation: EvaluationParams,
+    /// Seed of the opponent moves sampled from the model.
+    pub seed: u64,
+}
+
+impl Default for BeamSearchConfig {
+    fn default() -> Self {
+        BeamSearchConfig {
+            width: 10,
+            depth: 3,
+            opponent_policy: OpponentPolicy::Model,
+            evaluation: EvaluationParams::default(),
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct BeamNode {
+    state: GameState,
+    first_move: Vec<Action>,
+    value: f64,
+}
+
+/// Expands our moves turn after turn and keeps the `width` best distinct states.
+pub struct BeamSearch {
+    config: BeamSearchConfig,
+    rng: StdRng,
+    opponent_model: Option<OpponentModel>,
+}
+
+impl BeamSearch {
+    pub fn new(config: BeamSearchConfig) -> Self {
+        BeamSearch {
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+            opponent_model: None,
+        }
+    }
+
+    pub fn get_config(&self) -> &BeamSearchConfig {
+        &self.config
+    }
+
+    pub fn set_opponent_model(&mut self, opponent_model: OpponentModel) {
+        self.opponent_model = Some(opponent_model);
+    }
+
+    /// First move of `owner` leading to the best state found before `time` runs out.
+    pub fn search(&mut self, state: &GameState, owner: u8, time: &TimeManager) -> Vec<Action> {
+        // never worth an evaluation, a beam left with the root falls back to greedy
+        let mut beam = vec![BeamNode {
+            state: state.clone(),
+            first_move: Vec::new(),
+            value: 0.0,
+        }];
+        for depth in 0..self.config.depth {
+            if time.should_stop() {
+                break;
+            }
+            let next = self.expand(&beam, owner, depth == 0, time);
+            if next.is_empty() {
+                break;
+            }
+            beam = next;
+        }
+        beam.into_iter()
+            .max_by(|a, b| a.value.total_cmp(&b.value))
+            .map(|node| node.first_move)
+            .filter(|first_move| !first_move.is_empty())
+            .unwrap_or_else(|| play::found_actions(state, owner))
+    }
+
+    /// Children ranked by `evaluation::get_quick_score`, only the `width` kept get the full
+    /// evaluation. Stops expanding as soon as `time` runs out, and keeps the quick scores
+    /// when it runs out before every kept child is evaluated.
+    fn expand(
+        &mut self,
+        beam: &[BeamNode],
+        owner: u8,
+        is_first_turn: bool,
+        time: &TimeManager,
+    ) -> Vec<BeamNode> {
+        let mut seen: HashSet<u64> = HashSet::new();
+        let mut children: Vec<BeamNode> = Vec::new();
+        'expansion: for node in beam {
+            if turn_simulator::is_game_over(&node.state) {
+                if seen.insert(node.state.get_hash()) {
+                    keep_best(&mut children, node.clone(), self.config.width);
+                }
+                continue;
+            }
+            let opponent_move = self.find_opponent_move(&node.state, 1 - owner);
+            for our_move in find_moves(&node.state, owner) {
+                if time.should_stop() {
+                    break 'expansion;
+                }
+                let mut actions: [Vec<Action>; 2] = [Vec::new(), Vec::new()];
+                actions[owner as usize] = our_move.clone();
+                actions[1 - owner as usize] = opponent_move.clone();
+                let mut child = node.state.clone();
+                turn_simulator::simulate_turn(&mut child, &actions);
+                if !seen.insert(child.get_hash()) {
+                    continue;
+                }
+                let child = BeamNode {
+                    value: evaluation::get_quick_score(&child, owner, &self.config.evaluation),
+                    state: child,
+                    first_move: if is_first_turn {
+                        our_move
+                    } else {
+                        node.first_move.clone()
+                    },
+                };
+                keep_best(&mut children, child, self.config.width);
+            }
+        }
+        let mut scores = Vec::with_capacity(children.len());
+        for child in children.iter() {
+            if time.should_stop() {
+                return children;
+            }
+            scores.push(evaluation::get_score(
+                &child.state,
+                owner,
+                &self.config.evaluation,
+            ));
+        }
+        for (child, score) in children.iter_mut().zip(scores) {
+            child.value = score;
+        }
+        children.sort_by(|a, b| b.value.total_cmp(&a.value));
+        children
+    }
+
+    fn find_opponent_move(&mut self, state: &GameState, opponent: u8) -> Vec<Action> {
+        match (self.config.opponent_policy, self.opponent_model.as_ref()) {
+            (OpponentPolicy::Wait, _) => Vec::new(),
+            (OpponentPolicy::Model, Some(model)) if model.get_nb_turns() >= MIN_OBSERVED_TURNS => {
+                model.sample_actions(state, opponent, &mut self.rng)
+            }
+            _ => play::found_actions(state, opponent),
+        }
+    }
+}
+
+/// Inserts `node` in `nodes`, sorted by decreasing value, and keeps the `width` best.
+/// The other states are freed during the expansion, where the time is checked.
+fn keep_best(nodes: &mut Vec<BeamNode>, node: BeamNode, width: usize) {
+    let index = nodes.partition_point(|kept| kept.value >= node.value);
+    if index < width {
+        nodes.insert(index, node);
+        nodes.truncate(width);
+    }
+}
+
+/// Greedy move, then every move changing the action of a single root.
+fn find_moves(state: &GameState, owner: u8) -> Vec<Vec<Action>> {
+    let greedy = play::found_actions(state, owner);
+    let player = &state.players[owner as usize];
+    let mut moves = vec![greedy.clone()];
+    for (index, root) in state.get_roots_in_order(owner).into_iter().enumerate() {
+        for candidate in actions_finder::find_all_actions(&state.grid, player, root) {
+            if candidate != greedy[index] {
+                let mut variant = greedy.clone();
+                variant[index] = candidate;
+                moves.push(variant);
+            }
+        }
+    }
+    moves
+}
+
+pub struct BeamSearchStrategy {
+    beam_search: BeamSearch,
+}
+
+impl BeamSearchStrategy {
+    pub fn new(config: BeamSearchConfig) -> Self {
+        BeamSearchStrategy {
+            beam_search: BeamSearch::new(config),
+        }
+    }
+}
+
+impl Default for BeamSearchStrategy {
+    fn default() -> Self {
+        BeamSearchStrategy::new(BeamSearchConfig::default())
+    }
+}
+
+impl Strategy for BeamSearchStrategy {
+    fn get_name(&self) -> &'static str {
+        NAME
+    }
+
+    fn observe_opponent(&mut self, model: &OpponentModel) {
+        self.beam_search.set_opponent_model(model.clone());
+    }
+
+    fn play(&mut self, state: &GameState, time: &TimeManager) -> Vec<Action> {
+        let best = self.beam_search.search(state, MY_ID, time);
+        (0..state.get_roots_in_order(MY_ID).len())
+            .map(|index| best.get(index).copied().unwrap_or_else(action::wait))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        game_entities::{
+            cell,
+            cell_content::OrganEntity,
+            coord,
+            game_state::{wallet_from_counts, Entity, OPPONENT_ID},
+            organ,
+            organ_direction::OrganDirection,
+            organ_type::OrganType,
+            protein_wallet,
+        },
+        strategies::bot_memory::InferredTurn,
+    };
+
+    use std::time::Duration;
+
+    use super::*;
+
+    fn build_state() -> GameState {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "7 1 ROOT 0 2 N 0 2",
+            "2 1 A -1 0 X 0 0",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(
+            8,
+            3,
+            &entities,
+            [
+                wallet_from_counts(0, 0, 0, 0),
+                wallet_from_counts(2, 2, 2, 2),
+            ],
+            1,
+        )
+    }
+
+    #[test]
+    fn test_find_moves_vary_one_root() {
+        let state = build_state();
+        let moves = find_moves(&state, MY_ID);
+        let actions =
+            actions_finder::find_all_actions(&state.grid, state.get_me(), coord::new(0, 1));
+
+        assert!(moves.iter().all(|one_move| one_move.len() == 1));
+        assert_eq!(moves.len(), actions.len());
+    }
+
+    #[test]
+    fn test_search_goes_for_the_protein() {
+        let state = build_state();
+        let mut beam_search = BeamSearch::new(BeamSearchConfig {
+            opponent_policy: OpponentPolicy::Wait,
+            ..BeamSearchConfig::default()
+        });
+        let time = TimeManager::with_budget(Duration::from_millis(500), Duration::ZERO);
+        let best = beam_search.search(&state, MY_ID, &time);
+
+        assert_eq!(best.len(), 1);
+        assert_eq!(action::get_coord_target(best[0]), coord::new(1, 1));
+        let mut simulated = state.clone();
+        turn_simulator::simulate_turn(&mut simulated, &[Vec::new(), best]);
+        assert!(cell::is_owned_by(simulated.grid.get_cell(1, 1), MY_ID));
+    }
+
+    #[test]
+    fn test_keep_best() {
+        let state = build_state();
+        let node = |value: f64| BeamNode {
+            state: state.clone(),
+            first_move: Vec::new(),
+            value,
+        };
+        let mut nodes = Vec::new();
+        for value in [1.0, 3.0, 0.0, 2.0] {
+            keep_best(&mut nodes, node(value), 2);
+        }
+        let values: Vec<f64> = nodes.iter().map(|kept| kept.value).collect();
+
+        assert_eq!(values, vec![3.0, 2.0]);
+    }
+
+    #[test]
+    fn test_expand_removes_duplicates() {
+        let state = build_state();
+        let mut beam_search = BeamSearch::new(BeamSearchConfig {
+            width: 1000,
+            ..BeamSearchConfig::default()
+        });
+        let beam = vec![
+            BeamNode {
+                state: state.clone(),
+                first_move: Vec::new(),
+                value: 0.0,
+            };
+            2
+        ];
+        let children = beam_search.expand(&beam, MY_ID, true, &TimeManager::new());
+        let hashes: HashSet<u64> = children.iter().map(|node| node.state.get_hash()).collect();
+
+        assert_eq!(hashes.len(), children.len());
+        assert_eq!(children.len(), find_moves(&state, MY_ID).len());
+    }
+
+    #[test]
+    fn test_expand_checks_the_time_per_child() {
+        let state = build_state();
+        let mut beam_search = BeamSearch::new(BeamSearchConfig::default());
+        let beam = vec![BeamNode {
+            state: state.clone(),
+            first_move: Vec::new(),
+            value: 0.0,
+        }];
+        let children = beam_search.expand(&beam, MY_ID, true, &TimeManager::with_max_checks(4));
+
+        assert!(!children.is_empty());
+        assert!(children.len() <= 3);
+        assert!(find_moves(&state, MY_ID).len() > 3);
+    }
+
+    #[test]
+    fn test_expand_keeps_the_quick_scores_when_the_time_runs_out() {
+        let state = build_state();
+        let mut beam_search = BeamSearch::new(BeamSearchConfig::default());
+        let beam = vec![BeamNode {
+            state: state.clone(),
+            first_move: Vec::new(),
+            value: 0.0,
+        }];
+        // one check per move, then the first full evaluation stops
+        let checks = find_moves(&state, MY_ID).len() as u32 + 1;
+        let children =
+            beam_search.expand(&beam, MY_ID, true, &TimeManager::with_max_checks(checks));
+        let params = &beam_search.get_config().evaluation;
+
+        assert!(!children.is_empty());
+        assert!(children
+            .iter()
+            .all(|child| child.value == evaluation::get_quick_score(&child.state, MY_ID, params)));
+    }
+
+    #[test]
+    fn test_opponent_move_is_sampled_from_the_model() {
+        let state = build_state();
+        let busy_turn = InferredTurn {
+            turn: 1,
+            new_organs: vec![OrganEntity::new(
+                coord::new(0, 0),
+                organ::new(
+                    OPPONENT_ID,
+                    OrganType::Basic,
+                    OrganDirection::North,
+                    coord::new(0, 0),
+                ),
+            )],
+            spent: protein_wallet::new(),
+            income: [0; 4],
+            missed_actions: 0,
+        };
+        let mut beam_search = BeamSearch::new(BeamSearchConfig::default());
+
+        // greedy has nothing worth growing here
+        assert_eq!(
+            beam_search.find_opponent_move(&state, MY_ID),
+            vec![action::wait()]
+        );
+        beam_search.set_opponent_model(OpponentModel::from_history(&vec![busy_turn; 20]));
+        assert_ne!(
+            beam_search.find_opponent_move(&state, MY_ID),
+            vec![action::wait()]
+        );
+    }
+}
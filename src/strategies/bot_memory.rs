@@ -0,0 +1,237 @@
+use std::fmt;
+
+use crate::{
+    actions::{
+        action::{self, Action},
+        action_type::ActionType,
+    },
+    game_entities::{
+        cell,
+        cell_content::OrganEntity,
+        game_state::{GameState, MY_ID, OPPONENT_ID},
+        organ_type::OrganType,
+        protein::PROTEINS,
+        protein_wallet::{self, ProteinWallet},
+    },
+};
+
+/// What the opponent did between two turns, deduced from the states.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InferredTurn {
+    pub turn: u32,
+    /// Organs of the opponent that were not there the previous turn, new roots included.
+    pub new_organs: Vec<OrganEntity>,
+    /// Cost of the new organs.
+    pub spent: ProteinWallet,
+    /// Proteins gained by harvesting and absorbing, indexed by protein.
+    pub income: [u32; 4],
+    /// Our GROW and SPORE of the previous turn that did not place an organ.
+    pub missed_actions: usize,
+}
+
+impl InferredTurn {
+    /// Opponent organisms created by a SPORE.
+    pub fn get_nb_spores(&self) -> usize {
+        self.new_organs
+            .iter()
+            .filter(|organ| organ.organ_type == OrganType::Root)
+            .count()
+    }
+}
+
+impl fmt::Display for InferredTurn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "turn {}: opponent grew {} organs ({} spores), spent",
+            self.turn,
+            self.new_organs.len(),
+            self.get_nb_spores()
+        )?;
+        for protein in PROTEINS {
+            write!(
+                f,
+                " {}{}",
+                protein.to_str(),
+                protein_wallet::get(self.spent, protein)
+            )?;
+        }
+        write!(f, ", gained")?;
+        for protein in PROTEINS {
+            write!(f, " {}{}", protein.to_str(), self.income[protein as usize])?;
+        }
+        write!(f, ", {} of our actions missed", self.missed_actions)
+    }
+}
+
+/// Kept across turns: the previous state, our previous actions and what the opponent did.
+#[derive(Default)]
+pub struct BotMemory {
+    previous_state: Option<GameState>,
+    previous_actions: Vec<Action>,
+    /// Our actions the last inference was made with.
+    last_actions: Vec<Action>,
+    history: Vec<InferredTurn>,
+}
+
+impl BotMemory {
+    pub fn new() -> Self {
+        BotMemory::default()
+    }
+
+    /// To call with the state of each new turn, before playing.
+    pub fn record_turn(&mut self, state: &GameState) {
+        if let Some(previous) = self.previous_state.as_ref() {
+            let inferred = infer_turn(previous, state, &self.previous_actions);
+            self.history.push(inferred);
+        }
+        self.previous_state = Some(state.clone());
+        self.last_actions = std::mem::take(&mut self.previous_actions);
+    }
+
+    /// To call with the actions we issued this turn.
+    pub fn record_actions(&mut self, actions: &[Action]) {
+        self.previous_actions = actions.to_vec();
+    }
+
+    pub fn get_last_inference(&self) -> Option<&InferredTurn> {
+        self.history.last()
+    }
+
+    pub fn get_last_actions(&self) -> &[Action] {
+        &self.last_actions
+    }
+
+    pub fn get_history(&self) -> &[InferredTurn] {
+        &self.history
+    }
+}
+
+/// Opponent moves and our missed actions between `previous` and `current`.
+pub fn infer_turn(
+    previous: &GameState,
+    current: &GameState,
+    our_actions: &[Action],
+) -> InferredTurn {
+    let new_organs: Vec<OrganEntity> = current
+        .grid
+        .iter_organs_of_owner(OPPONENT_ID)
+        .filter(|organ| current.get_organ_id(organ.coord) != previous.get_organ_id(organ.coord))
+        .collect();
+    let mut spent = protein_wallet::new();
+    for organ in new_organs.iter() {
+        spent += organ.organ_type.get_cost();
+    }
+    let before = previous.get_opponent().get_wallet();
+    let after = current.get_opponent().get_wallet();
+    let income = PROTEINS.map(|protein| {
+        (protein_wallet::get(after, protein) as u32 + protein_wallet::get(spent, protein) as u32)
+            .saturating_sub(protein_wallet::get(before, protein) as u32)
+    });
+    let missed_actions = our_actions
+        .iter()
+        .filter(|&&our_action| action::get_type(our_action) != ActionType::Wait)
+        .filter(|&&our_action| {
+            let target = action::get_coord_target(our_action);
+            !cell::is_owned_by(current.grid.get_cell_from_coord(target), MY_ID)
+                || current.get_organ_id(target) == previous.get_organ_id(target)
+        })
+        .count();
+    InferredTurn {
+        turn: current.turn,
+        new_organs,
+        spent,
+        income,
+        missed_actions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::{
+        coord,
+        game_state::{wallet_from_counts, Entity},
+        organ_direction::OrganDirection,
+    };
+
+    use super::*;
+
+    fn build_state(lines: &[&str], opponent_wallet: ProteinWallet, turn: u32) -> GameState {
+        let entities: Vec<Entity> = lines.iter().map(|line| Entity::from_line(line)).collect();
+        let mut state = GameState::from_entities(
+            8,
+            3,
+            &entities,
+            [opponent_wallet, wallet_from_counts(5, 5, 5, 5)],
+            1,
+        );
+        state.turn = turn;
+        state
+    }
+
+    fn previous_state() -> GameState {
+        build_state(
+            &["0 1 ROOT 1 1 N 0 1", "7 1 ROOT 0 2 N 0 2"],
+            wallet_from_counts(2, 2, 2, 2),
+            1,
+        )
+    }
+
+    fn current_state() -> GameState {
+        build_state(
+            &[
+                "0 1 ROOT 1 1 N 0 1",
+                "7 1 ROOT 0 2 N 0 2",
+                "1 1 BASIC 1 3 E 1 1",
+                "6 1 HARVESTER 0 4 W 2 2",
+                "4 0 ROOT 0 5 N 0 5",
+            ],
+            wallet_from_counts(2, 1, 1, 3),
+            2,
+        )
+    }
+
+    #[test]
+    fn test_infer_turn() {
+        let our_actions = [
+            action::growth(
+                OrganType::Basic,
+                OrganDirection::East,
+                coord::new(1, 1),
+                coord::new(0, 1),
+            ),
+            action::growth(
+                OrganType::Basic,
+                OrganDirection::East,
+                coord::new(0, 0),
+                coord::new(0, 1),
+            ),
+        ];
+        let inferred = infer_turn(&previous_state(), &current_state(), &our_actions);
+
+        assert_eq!(inferred.turn, 2);
+        assert_eq!(inferred.new_organs.len(), 2);
+        assert_eq!(inferred.get_nb_spores(), 1);
+        // HARVESTER costs B and C, ROOT one of each
+        assert_eq!(inferred.spent, wallet_from_counts(1, 2, 2, 1));
+        assert_eq!(inferred.income, [1, 1, 1, 2]);
+        assert_eq!(inferred.missed_actions, 1);
+    }
+
+    #[test]
+    fn test_memory_keeps_the_history() {
+        let mut memory = BotMemory::new();
+
+        memory.record_turn(&previous_state());
+        assert!(memory.get_last_inference().is_none());
+        memory.record_actions(&[action::wait()]);
+        memory.record_turn(&current_state());
+        let inferred = memory.get_last_inference().unwrap();
+        assert_eq!(inferred.missed_actions, 0);
+        assert_eq!(
+            inferred.to_string(),
+            "turn 2: opponent grew 2 organs (1 spores), spent A1 B2 C2 D1, \
+             gained A1 B1 C1 D2, 0 of our actions missed"
+        );
+    }
+}
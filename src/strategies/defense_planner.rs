@@ -0,0 +1,250 @@
+use std::collections::HashSet;
+
+use crate::{
+    actions::{
+        action::{self, Action},
+        actions_finder::DIRECTIONS,
+    },
+    entites_managers::proteins_manager::{ProteinsManager, UNREACHABLE},
+    game_entities::{
+        cell,
+        coord::{self, Coord},
+        grid::Grid,
+        organ,
+        organ_type::OrganType,
+    },
+};
+
+/// Enemy organs at most this many growths away from a cell are approaching it.
+pub const APPROACH_DISTANCE: u8 = 2;
+
+/// A harvester is worth the income it brings on top of the organ.
+pub const HARVESTER_VALUE: f64 = 3.0;
+
+/// Value of each cell the opponent no longer reaches first once a choke point is blocked.
+pub const CHOKE_WEIGHT: f64 = 0.5;
+
+/// TENTACLE facing the approach of an enemy, valued by the subtrees it protects.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DefenseProposal {
+    pub action: Action,
+    pub value: f64,
+}
+
+/// Detects the enemy organs approaching our organs and the corridors they use.
+pub struct DefensePlanner {
+    owner: u8,
+    proteins_manager: ProteinsManager,
+    opponent_territory: usize,
+}
+
+impl DefensePlanner {
+    pub fn new(grid: &Grid, owner: u8) -> Self {
+        let proteins_manager = ProteinsManager::new(grid);
+        let opponent_territory =
+            count_opponent_territory(grid, &proteins_manager, &proteins_manager, owner);
+        DefensePlanner {
+            owner,
+            proteins_manager,
+            opponent_territory,
+        }
+    }
+
+    /// True when `coord` holds an enemy organ or an enemy organ can reach it soon.
+    pub fn is_approached(&self, grid: &Grid, coord: Coord) -> bool {
+        let cell = grid.get_cell_from_coord(coord);
+        if cell::is_organ(cell) {
+            return cell::is_owned_by(cell, 1 - self.owner);
+        }
+        cell::is_growable(cell)
+            && self
+                .proteins_manager
+                .get_distance(grid, 1 - self.owner, coord)
+                <= APPROACH_DISTANCE
+    }
+
+    /// Every TENTACLE of the organism rooted at `root_coord` facing an approached cell,
+    /// best proposal first.
+    pub fn find_proposals(&self, grid: &Grid, root_coord: Coord) -> Vec<DefenseProposal> {
+        let mut proposals = Vec::new();
+        for coord in grid.iter_root_frontier(self.owner, root_coord) {
+            for direction in DIRECTIONS {
+                let faced = match grid.get_next_coord(coord, direction) {
+                    Some(faced) if self.is_approached(grid, faced) => faced,
+                    _ => continue,
+                };
+                let value = self.get_protected_value(grid, &[coord, faced])
+                    + self.get_choke_value(grid, faced);
+                if value > 0.0 {
+                    proposals.push(DefenseProposal {
+                        action: action::growth(OrganType::Tentacle, direction, coord, root_coord),
+                        value,
+                    });
+                }
+            }
+        }
+        proposals.sort_by(|a, b| b.value.total_cmp(&a.value));
+        proposals
+    }
+
+    /// Value of our subtrees having an organ next to one of `coords`.
+    pub fn get_protected_value(&self, grid: &Grid, coords: &[Coord]) -> f64 {
+        let mut protected: HashSet<Coord> = HashSet::new();
+        for &coord in coords {
+            for adjacent in grid.get_adjacent_coords(coord) {
+                if cell::is_owned_by(grid.get_cell_from_coord(adjacent), self.owner) {
+                    protected.extend(grid.get_subtree(adjacent));
+                }
+            }
+        }
+        protected
+            .into_iter()
+            .map(
+                |coord| match cell::get_organ(grid.get_cell_from_coord(coord)) {
+                    Some(organ) if organ::is_harvester(organ) => HARVESTER_VALUE,
+                    Some(_) => 1.0,
+                    None => 0.0,
+                },
+            )
+            .sum()
+    }
+
+    /// Cells the opponent would stop reaching first if it could not grow on `coord`.
+    pub fn get_choke_value(&self, grid: &Grid, coord: Coord) -> f64 {
+        if !cell::is_growable(grid.get_cell_from_coord(coord)) {
+            return 0.0;
+        }
+        let mut blocked = grid.clone();
+        blocked.set_cell(coord::x(coord), coord::y(coord), cell::new_wall());
+        let territory = count_opponent_territory(
+            grid,
+            &ProteinsManager::new(&blocked),
+            &self.proteins_manager,
+            self.owner,
+        );
+        let was_taken = self
+            .proteins_manager
+            .get_distance(grid, 1 - self.owner, coord)
+            < self.proteins_manager.get_distance(grid, self.owner, coord);
+        // the blocked cell itself is not a corridor loss
+        let lost = self.opponent_territory - territory - usize::from(was_taken);
+        CHOKE_WEIGHT * lost as f64
+    }
+}
+
+/// Growable cells the opponent of `owner` reaches strictly before `owner`, the distances
+/// of the opponent coming from `opponent_manager` and those of `owner` from `own_manager`.
+fn count_opponent_territory(
+    grid: &Grid,
+    opponent_manager: &ProteinsManager,
+    own_manager: &ProteinsManager,
+    owner: u8,
+) -> usize {
+    grid.iter_coords()
+        .filter(|&coord| cell::is_growable(grid.get_cell_from_coord(coord)))
+        .filter(|&coord| {
+            let distance = opponent_manager.get_distance(grid, 1 - owner, coord);
+            distance != UNREACHABLE && distance < own_manager.get_distance(grid, owner, coord)
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::{
+        coord,
+        game_state::{wallet_from_counts, Entity, GameState, MY_ID},
+        organ_direction::OrganDirection,
+    };
+
+    use super::*;
+
+    fn build_state(opponent_x: u8) -> GameState {
+        let opponent_root = format!("{} 1 ROOT 0 3 N 0 3", opponent_x);
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "1 1 HARVESTER 1 2 N 1 1",
+            "1 0 A -1 0 X 0 0",
+            opponent_root.as_str(),
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(
+            opponent_x + 1,
+            3,
+            &entities,
+            [
+                wallet_from_counts(0, 0, 0, 0),
+                wallet_from_counts(0, 1, 0, 1),
+            ],
+            1,
+        )
+    }
+
+    #[test]
+    fn test_protected_value_counts_subtrees() {
+        let state = build_state(3);
+        let planner = DefensePlanner::new(&state.grid, MY_ID);
+
+        // the harvester alone, then the root and its whole subtree
+        assert_eq!(
+            planner.get_protected_value(&state.grid, &[coord::new(2, 1)]),
+            HARVESTER_VALUE
+        );
+        assert_eq!(
+            planner.get_protected_value(&state.grid, &[coord::new(0, 0)]),
+            1.0 + HARVESTER_VALUE
+        );
+    }
+
+    #[test]
+    fn test_tentacle_facing_an_intruder() {
+        let state = build_state(3);
+        let planner = DefensePlanner::new(&state.grid, MY_ID);
+        let proposals = planner.find_proposals(&state.grid, coord::new(0, 1));
+        let kill = action::growth(
+            OrganType::Tentacle,
+            OrganDirection::East,
+            coord::new(2, 1),
+            coord::new(0, 1),
+        );
+
+        assert!(proposals
+            .windows(2)
+            .all(|pair| pair[0].value >= pair[1].value));
+        assert!(proposals.iter().all(|proposal| action::is_valid_with_root(
+            proposal.action,
+            &state.grid,
+            state.get_me()
+        )));
+        let proposal = proposals.iter().find(|proposal| proposal.action == kill);
+        assert!(proposal.is_some_and(|proposal| proposal.value >= HARVESTER_VALUE));
+    }
+
+    #[test]
+    fn test_no_proposal_without_approach() {
+        let state = build_state(9);
+        let planner = DefensePlanner::new(&state.grid, MY_ID);
+
+        assert!(!planner.is_approached(&state.grid, coord::new(2, 1)));
+        assert!(planner
+            .find_proposals(&state.grid, coord::new(0, 1))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_choke_value_of_a_corridor() {
+        let mut state = build_state(9);
+        state.grid.set_cell(7, 0, cell::new_wall());
+        state.grid.set_cell(7, 2, cell::new_wall());
+        let planner = DefensePlanner::new(&state.grid, MY_ID);
+
+        // blocking the gate keeps the opponent out of the column it reaches first
+        assert_eq!(
+            planner.get_choke_value(&state.grid, coord::new(7, 1)),
+            3.0 * CHOKE_WEIGHT
+        );
+        assert_eq!(planner.get_choke_value(&state.grid, coord::new(0, 0)), 0.0);
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+
+use crate::{
+    actions::{
+        action::{self, Action},
+        actions_finder,
+    },
+    entites_managers::proteins_manager::{ProteinSourceReport, ProteinsManager},
+    game_entities::{
+        coord::Coord, grid::Grid, organ, player::Player, protein::PROTEINS, protein_wallet,
+    },
+    simulation::turn_simulator,
+};
+
+/// Turns over which the income of the opponent is projected.
+pub const DENIAL_HORIZON: u32 = 10;
+
+/// Unharvested sources the opponent can harvest within this many growths are its targets.
+pub const TARGET_DISTANCE: u8 = 3;
+
+/// Action lowering the projected income of the opponent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DenialProposal {
+    pub action: Action,
+    pub income_reduction: u32,
+    /// Income denied per protein spent.
+    pub value: f64,
+}
+
+/// Proteins `owner` should gain over `DENIAL_HORIZON` turns from its harvested sources
+/// and the sources it targets.
+pub fn get_projected_income(grid: &Grid, owner: u8) -> u32 {
+    let proteins_manager = ProteinsManager::new(grid);
+    proteins_manager
+        .get_reports()
+        .iter()
+        .map(|report| {
+            if report.harvested_by[owner as usize] {
+                DENIAL_HORIZON
+            } else if is_targeted_by(report, owner) {
+                report.get_harvest_income(owner, DENIAL_HORIZON)
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+fn is_targeted_by(report: &ProteinSourceReport, owner: u8) -> bool {
+    !report.is_harvested() && report.harvest_distances[owner as usize] <= TARGET_DISTANCE
+}
+
+/// Sources the opponent of `owner` harvests or targets.
+pub fn get_opponent_sources(grid: &Grid, owner: u8) -> Vec<Coord> {
+    let proteins_manager = ProteinsManager::new(grid);
+    let opponent = 1 - owner;
+    grid.iter_protein_sources()
+        .map(|source| source.coord)
+        .filter(|&coord| {
+            proteins_manager.get_report(coord).is_some_and(|report| {
+                report.harvested_by[opponent as usize] || is_targeted_by(report, opponent)
+            })
+        })
+        .collect()
+}
+
+/// GROWs of the organism rooted at `root_coord` around a source of the opponent,
+/// absorbing it or blocking its harvester spots, best income denied per protein first.
+pub fn find_denials(grid: &Grid, player: &Player, root_coord: Coord) -> Vec<DenialProposal> {
+    let owner = player.get_id();
+    let sources = get_opponent_sources(grid, owner);
+    if sources.is_empty() {
+        return Vec::new();
+    }
+    // the sources, their harvester spots and the cells a tentacle blocks the spots from
+    let mut relevant: HashSet<Coord> = sources.iter().copied().collect();
+    for _ in 0..2 {
+        let around: Vec<Coord> = relevant
+            .iter()
+            .flat_map(|&coord| grid.get_adjacent_coords(coord))
+            .collect();
+        relevant.extend(around);
+    }
+    let income = get_projected_income(grid, 1 - owner);
+    let mut denials = Vec::new();
+    for action in actions_finder::find_growth_actions(grid, player, root_coord) {
+        let target = action::get_coord_target(action);
+        let organ = match turn_simulator::get_placed_organ(grid, owner, action) {
+            Some(organ) if relevant.contains(&target) => organ,
+            _ => continue,
+        };
+        let mut denied = grid.clone();
+        denied.add_organ(target, organ);
+        let income_reduction = income.saturating_sub(get_projected_income(&denied, 1 - owner));
+        if income_reduction == 0 {
+            continue;
+        }
+        let cost = organ::get_type(organ).get_cost();
+        let spent: u32 = PROTEINS
+            .iter()
+            .map(|&protein| protein_wallet::get(cost, protein) as u32)
+            .sum();
+        denials.push(DenialProposal {
+            action,
+            income_reduction,
+            value: income_reduction as f64 / spent.max(1) as f64,
+        });
+    }
+    denials.sort_by(|a, b| b.value.total_cmp(&a.value));
+    denials
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::{
+        coord,
+        game_state::{wallet_from_counts, Entity, GameState, MY_ID, OPPONENT_ID},
+        organ_direction::OrganDirection,
+        organ_type::OrganType,
+    };
+
+    use super::*;
+
+    fn build_state(entities: &[&str], width: u8) -> GameState {
+        let entities: Vec<Entity> = entities
+            .iter()
+            .map(|line| Entity::from_line(line))
+            .collect();
+        GameState::from_entities(
+            width,
+            3,
+            &entities,
+            [
+                wallet_from_counts(0, 0, 0, 0),
+                wallet_from_counts(1, 1, 1, 1),
+            ],
+            1,
+        )
+    }
+
+    fn build_harvested_state() -> GameState {
+        build_state(
+            &[
+                "1 1 ROOT 1 1 N 0 1",
+                "2 1 BASIC 1 2 N 1 1",
+                "3 1 A -1 0 X 0 0",
+                "4 1 HARVESTER 0 3 W 4 4",
+                "5 1 ROOT 0 4 N 0 4",
+            ],
+            7,
+        )
+    }
+
+    #[test]
+    fn test_projected_income() {
+        let mut state = build_harvested_state();
+
+        assert_eq!(
+            get_projected_income(&state.grid, OPPONENT_ID),
+            DENIAL_HORIZON
+        );
+        assert_eq!(
+            get_opponent_sources(&state.grid, MY_ID),
+            vec![coord::new(3, 1)]
+        );
+        // without its harvester, the opponent needs one growth to harvest again
+        state.grid.remove_organ(coord::new(4, 1));
+        assert_eq!(
+            get_projected_income(&state.grid, OPPONENT_ID),
+            DENIAL_HORIZON - 1
+        );
+    }
+
+    #[test]
+    fn test_absorbing_the_harvested_source() {
+        let state = build_harvested_state();
+        let denials = find_denials(&state.grid, state.get_me(), coord::new(1, 1));
+
+        assert!(denials
+            .windows(2)
+            .all(|pair| pair[0].value >= pair[1].value));
+        let best = denials[0];
+        assert_eq!(action::get_coord_target(best.action), coord::new(3, 1));
+        assert_eq!(action::get_organ_type(best.action), OrganType::Basic);
+        assert_eq!(best.income_reduction, DENIAL_HORIZON);
+        assert_eq!(best.value, DENIAL_HORIZON as f64);
+    }
+
+    #[test]
+    fn test_tentacle_blocking_the_harvester_spot() {
+        // (4, 1) is the only cell from where the opponent can harvest (4, 0)
+        let state = build_state(
+            &[
+                "1 1 ROOT 1 1 N 0 1",
+                "2 1 BASIC 1 2 N 1 1",
+                "4 0 A -1 0 X 0 0",
+                "3 0 WALL -1 0 X 0 0",
+                "4 2 ROOT 0 3 N 0 3",
+            ],
+            5,
+        );
+        let denials = find_denials(&state.grid, state.get_me(), coord::new(1, 1));
+
+        assert_eq!(denials.len(), 1);
+        assert_eq!(
+            denials[0].action,
+            action::growth(
+                OrganType::Tentacle,
+                OrganDirection::East,
+                coord::new(3, 1),
+                coord::new(1, 1)
+            )
+        );
+        assert_eq!(denials[0].income_reduction, DENIAL_HORIZON - 1);
+    }
+}
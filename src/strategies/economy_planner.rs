@@ -0,0 +1,215 @@
+use crate::{
+    actions::actions_finder::GROWABLE_TYPES,
+    entites_managers::proteins_manager::{ProteinsManager, ABSORB_GAIN},
+    game_entities::{
+        game_state::GameState,
+        organ_type::OrganType,
+        protein::{Protein, PROTEINS},
+        protein_wallet::{self, ProteinWallet},
+    },
+};
+
+/// Organ we want to be able to buy within `deadline` turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PurchaseGoal {
+    pub organ_type: OrganType,
+    pub deadline: u32,
+}
+
+/// Projects the wallet of a player from its harvesters and the absorptions it plans.
+#[derive(Debug, Clone)]
+pub struct EconomyPlanner {
+    wallet: [u32; 4],
+    /// Proteins gained per turn, one per harvested source.
+    income: [u32; 4],
+    /// Absorptions as (turn, protein).
+    absorptions: Vec<(u32, Protein)>,
+}
+
+impl EconomyPlanner {
+    pub fn new(state: &GameState, owner: u8) -> Self {
+        let player = &state.players[owner as usize];
+        let mut income = [0; 4];
+        for report in ProteinsManager::new(&state.grid).get_reports() {
+            if report.harvested_by[owner as usize] {
+                income[report.protein as usize] += 1;
+            }
+        }
+        EconomyPlanner {
+            wallet: PROTEINS.map(|protein| player.get_nb_protein(protein)),
+            income,
+            absorptions: Vec::new(),
+        }
+    }
+
+    pub fn get_income(&self, protein: Protein) -> u32 {
+        self.income[protein as usize]
+    }
+
+    pub fn add_absorption(&mut self, turn: u32, protein: Protein) {
+        self.absorptions.push((turn, protein));
+    }
+
+    /// A harvester grown now on a source of `protein`, harvested from the next turn.
+    pub fn add_harvest(&mut self, protein: Protein) {
+        self.income[protein as usize] += 1;
+    }
+
+    /// Amount of `protein` in `turns` turns if nothing is bought.
+    pub fn get_projected_amount(&self, protein: Protein, turns: u32) -> u32 {
+        let absorbed = self
+            .absorptions
+            .iter()
+            .filter(|&&(turn, absorbed)| turn <= turns && absorbed == protein)
+            .count() as u32;
+        self.wallet[protein as usize]
+            + self.income[protein as usize] * turns
+            + absorbed * ABSORB_GAIN
+    }
+
+    pub fn get_projected_wallet(&self, turns: u32) -> ProteinWallet {
+        let mut wallet = protein_wallet::new();
+        for protein in PROTEINS {
+            protein_wallet::add(
+                &mut wallet,
+                protein,
+                self.get_projected_amount(protein, turns),
+            );
+        }
+        wallet
+    }
+
+    /// First turn, up to `horizon`, where `organ_type` is affordable.
+    pub fn get_turns_to_afford(&self, organ_type: OrganType, horizon: u32) -> Option<u32> {
+        (0..=horizon).find(|&turns| {
+            protein_wallet::can_buy_organ(self.get_projected_wallet(turns), organ_type)
+        })
+    }
+
+    /// Same planner once `organ_type` is bought now.
+    pub fn after_purchase(&self, organ_type: OrganType) -> EconomyPlanner {
+        let mut planner = self.clone();
+        let cost = organ_type.get_cost();
+        for protein in PROTEINS {
+            let amount = &mut planner.wallet[protein as usize];
+            *amount = amount.saturating_sub(protein_wallet::get(cost, protein) as u32);
+        }
+        planner
+    }
+
+    fn reaches(&self, goal: &PurchaseGoal) -> bool {
+        self.get_turns_to_afford(goal.organ_type, goal.deadline)
+            .is_some()
+    }
+
+    /// Organ types affordable now whose purchase keeps every reachable goal reachable in time.
+    pub fn get_allowed_purchases(&self, goals: &[PurchaseGoal]) -> Vec<OrganType> {
+        let current_wallet = self.get_projected_wallet(0);
+        GROWABLE_TYPES
+            .iter()
+            .copied()
+            .filter(|&organ_type| protein_wallet::can_buy_organ(current_wallet, organ_type))
+            .filter(|&organ_type| {
+                let after = self.after_purchase(organ_type);
+                goals.iter().all(|goal| {
+                    goal.organ_type == organ_type || !self.reaches(goal) || after.reaches(goal)
+                })
+            })
+            .collect()
+    }
+
+    /// Proteins lacking to reach the goals in time, a harvester on them is needed.
+    pub fn get_needed_harvests(&self, goals: &[PurchaseGoal]) -> Vec<Protein> {
+        let mut needed = Vec::new();
+        for goal in goals.iter().filter(|goal| !self.reaches(goal)) {
+            let cost = goal.organ_type.get_cost();
+            for protein in PROTEINS {
+                let lacking = self.get_projected_amount(protein, goal.deadline)
+                    < protein_wallet::get(cost, protein) as u32;
+                if lacking && !needed.contains(&protein) {
+                    needed.push(protein);
+                }
+            }
+        }
+        needed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::game_state::{wallet_from_counts, Entity, MY_ID};
+
+    use super::*;
+
+    fn build_state(wallet: ProteinWallet) -> GameState {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "1 1 HARVESTER 1 2 E 1 1",
+            "6 1 ROOT 0 3 N 0 3",
+            "2 1 C -1 0 X 0 0",
+            "4 0 D -1 0 X 0 0",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(7, 3, &entities, [0, wallet], 1)
+    }
+
+    #[test]
+    fn test_projection_from_income_and_absorptions() {
+        let mut planner = EconomyPlanner::new(&build_state(wallet_from_counts(1, 0, 2, 0)), MY_ID);
+
+        assert_eq!(planner.get_income(Protein::C), 1);
+        assert_eq!(planner.get_income(Protein::D), 0);
+        assert_eq!(planner.get_projected_amount(Protein::C, 3), 5);
+        planner.add_absorption(2, Protein::D);
+        assert_eq!(planner.get_projected_amount(Protein::D, 1), 0);
+        assert_eq!(planner.get_projected_amount(Protein::D, 2), ABSORB_GAIN);
+    }
+
+    #[test]
+    fn test_turns_to_afford() {
+        let planner = EconomyPlanner::new(&build_state(wallet_from_counts(0, 1, 0, 0)), MY_ID);
+
+        // HARVESTER costs B and C, C comes from the harvester
+        assert_eq!(
+            planner.get_turns_to_afford(OrganType::Harvester, 5),
+            Some(1)
+        );
+        // SPORER costs C and D, nobody harvests D
+        assert_eq!(planner.get_turns_to_afford(OrganType::Sporer, 5), None);
+    }
+
+    #[test]
+    fn test_allowed_purchases_keep_the_goal() {
+        let planner = EconomyPlanner::new(&build_state(wallet_from_counts(1, 1, 1, 1)), MY_ID);
+        let goals = [PurchaseGoal {
+            organ_type: OrganType::Tentacle,
+            deadline: 2,
+        }];
+
+        // buying a sporer spends the only D the tentacle needs
+        let allowed = planner.get_allowed_purchases(&goals);
+        assert!(allowed.contains(&OrganType::Basic));
+        assert!(allowed.contains(&OrganType::Tentacle));
+        assert!(!allowed.contains(&OrganType::Sporer));
+        assert_eq!(planner.get_allowed_purchases(&[]).len(), 4);
+    }
+
+    #[test]
+    fn test_needed_harvests() {
+        let planner = EconomyPlanner::new(&build_state(wallet_from_counts(0, 0, 0, 0)), MY_ID);
+        let goals = [PurchaseGoal {
+            organ_type: OrganType::Sporer,
+            deadline: 10,
+        }];
+
+        assert_eq!(planner.get_needed_harvests(&goals), vec![Protein::D]);
+        assert!(planner
+            .get_needed_harvests(&[PurchaseGoal {
+                organ_type: OrganType::Harvester,
+                deadline: 0,
+            }])
+            .contains(&Protein::B));
+    }
+}
@@ -0,0 +1,215 @@
+use std::{cmp::Reverse, collections::HashSet};
+
+use crate::{
+    actions::{
+        action::{self, Action},
+        actions_finder::{DIRECTIONS, GROWABLE_TYPES},
+    },
+    entites_managers::proteins_manager::{ProteinsManager, UNREACHABLE},
+    game_entities::{
+        cell,
+        coord::Coord,
+        game_state::GameState,
+        grid::Grid,
+        organ_direction::OrganDirection,
+        organ_type::OrganType,
+        protein::{Protein, PROTEINS},
+        protein_wallet::{self, ProteinWallet},
+    },
+    simulation::turn_simulator::MAX_TURNS,
+};
+
+use super::economy_planner::EconomyPlanner;
+
+/// True when no cell can be reached by both players, the game being then a solo puzzle.
+pub fn is_separated(grid: &Grid) -> bool {
+    let proteins_manager = ProteinsManager::new(grid);
+    grid.iter_coords().all(|coord| {
+        proteins_manager.get_distance(grid, 0, coord) == UNREACHABLE
+            || proteins_manager.get_distance(grid, 1, coord) == UNREACHABLE
+    })
+}
+
+/// Most organs `wallet` can still buy: one BASIC per A, then the B, C and D paired
+/// by HARVESTERs, SPORERs and TENTACLEs.
+pub fn get_max_organs(wallet: ProteinWallet) -> u32 {
+    let [a, b, c, d] = PROTEINS.map(|protein| protein_wallet::get(wallet, protein) as u32);
+    a + ((b + c + d) / 2).min(b + c).min(b + d).min(c + d)
+}
+
+/// One GROW per root filling its area: free sources first to absorb them, the sources
+/// we harvest last, with the organ type keeping the most organs affordable by the end
+/// of the game, a HARVESTER facing a free source adding its income.
+pub fn fill(state: &GameState, owner: u8) -> Vec<Action> {
+    let grid = &state.grid;
+    let proteins_manager = ProteinsManager::new(grid);
+    // the proteins harvested on the last turn are never spent
+    let remaining_turns = MAX_TURNS.saturating_sub(state.turn + 1);
+    let mut planner = EconomyPlanner::new(state, owner);
+    let mut targets: HashSet<Coord> = HashSet::new();
+    let mut actions = Vec::new();
+    for root in state.get_roots_in_order(owner) {
+        let mut cells: Vec<Coord> = grid
+            .iter_root_frontier(owner, root)
+            .filter(|coord| !targets.contains(coord))
+            .collect();
+        cells.sort_by_key(|&coord| match proteins_manager.get_report(coord) {
+            Some(report) if report.harvested_by[owner as usize] => 2,
+            Some(_) => 0,
+            None => 1,
+        });
+        let target = match cells.first() {
+            Some(&target) => target,
+            None => {
+                actions.push(action::wait());
+                continue;
+            }
+        };
+        let free_source = get_free_source(grid, &proteins_manager, owner, target);
+        let wallet = planner.get_projected_wallet(0);
+        let best = GROWABLE_TYPES
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|&(_, organ_type)| protein_wallet::can_buy_organ(wallet, organ_type))
+            .map(|(index, organ_type)| {
+                let mut after = planner.after_purchase(organ_type);
+                if let (OrganType::Harvester, Some((_, protein))) = (organ_type, free_source) {
+                    after.add_harvest(protein);
+                }
+                (index, organ_type, after)
+            })
+            .max_by_key(|(index, _, after)| {
+                // on a tie the first type, so BASIC, is kept
+                (
+                    get_max_organs(after.get_projected_wallet(remaining_turns)),
+                    Reverse(*index),
+                )
+            });
+        let action = match best {
+            Some((_, organ_type, after)) => {
+                planner = after;
+                targets.insert(target);
+                let direction = free_source
+                    .map(|(direction, _)| direction)
+                    .unwrap_or(OrganDirection::North);
+                action::growth(organ_type, direction, target, root)
+            }
+            None => action::wait(),
+        };
+        actions.push(action);
+    }
+    actions
+}
+
+/// Direction from `coord` to an adjacent source `owner` does not harvest yet, and its protein.
+fn get_free_source(
+    grid: &Grid,
+    proteins_manager: &ProteinsManager,
+    owner: u8,
+    coord: Coord,
+) -> Option<(OrganDirection, Protein)> {
+    DIRECTIONS.iter().copied().find_map(|direction| {
+        grid.get_next_coord(coord, direction)
+            .filter(|&faced| cell::is_protein(grid.get_cell_from_coord(faced)))
+            .and_then(|faced| proteins_manager.get_report(faced))
+            .filter(|report| !report.harvested_by[owner as usize])
+            .map(|report| (direction, report.protein))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::{
+        coord,
+        game_state::{wallet_from_counts, Entity, MY_ID},
+        organ_type::OrganType,
+    };
+
+    use super::*;
+
+    fn build_state(wallet: ProteinWallet) -> GameState {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "0 3 ROOT 1 2 N 0 2",
+            "1 1 A -1 0 X 0 0",
+            "3 0 WALL -1 0 X 0 0",
+            "3 1 WALL -1 0 X 0 0",
+            "3 2 WALL -1 0 X 0 0",
+            "3 3 WALL -1 0 X 0 0",
+            "5 1 ROOT 0 3 N 0 3",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(6, 4, &entities, [0, wallet], 2)
+    }
+
+    #[test]
+    fn test_is_separated() {
+        let mut state = build_state(0);
+
+        assert!(is_separated(&state.grid));
+        state.grid.set_cell(3, 2, cell::new(false, None, None));
+        assert!(!is_separated(&state.grid));
+    }
+
+    #[test]
+    fn test_max_organs() {
+        assert_eq!(get_max_organs(wallet_from_counts(2, 1, 1, 1)), 3);
+        assert_eq!(get_max_organs(wallet_from_counts(0, 3, 1, 0)), 1);
+        assert_eq!(get_max_organs(wallet_from_counts(0, 2, 2, 2)), 3);
+    }
+
+    #[test]
+    fn test_fill_absorbs_free_sources_first() {
+        let state = build_state(wallet_from_counts(2, 0, 0, 0));
+        let actions = fill(&state, MY_ID);
+
+        assert_eq!(actions.len(), 2);
+        assert_eq!(action::get_coord_target(actions[0]), coord::new(1, 1));
+        assert_eq!(action::get_organ_type(actions[0]), OrganType::Basic);
+        assert_ne!(
+            action::get_coord_target(actions[0]),
+            action::get_coord_target(actions[1])
+        );
+        assert!(actions.iter().all(|&action| action::is_valid_with_root(
+            action,
+            &state.grid,
+            state.get_me()
+        )));
+    }
+
+    #[test]
+    fn test_fill_harvests_a_free_source_while_it_pays() {
+        let entities: Vec<Entity> = [
+            "0 0 ROOT 1 1 N 0 1",
+            "2 0 A -1 0 X 0 0",
+            "4 0 WALL -1 0 X 0 0",
+            "5 0 ROOT 0 2 N 0 2",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        let mut state =
+            GameState::from_entities(6, 1, &entities, [0, wallet_from_counts(1, 1, 1, 0)], 1);
+
+        // the income of the HARVESTER buys more organs than the BASIC it costs
+        let actions = fill(&state, MY_ID);
+        assert_eq!(action::get_organ_type(actions[0]), OrganType::Harvester);
+        assert_eq!(action::get_direction(actions[0]), OrganDirection::East);
+        state.turn = MAX_TURNS - 1;
+        let actions = fill(&state, MY_ID);
+        assert_eq!(action::get_organ_type(actions[0]), OrganType::Basic);
+    }
+
+    #[test]
+    fn test_fill_keeps_the_stock_balanced() {
+        let state = build_state(wallet_from_counts(0, 2, 1, 1));
+        let actions = fill(&state, MY_ID);
+
+        // a SPORER would leave only B, a HARVESTER keeps a TENTACLE affordable
+        assert_eq!(action::get_organ_type(actions[0]), OrganType::Harvester);
+        assert_eq!(action::get_organ_type(actions[1]), OrganType::Tentacle);
+    }
+}
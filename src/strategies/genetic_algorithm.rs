@@ -0,0 +1,652 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{
+    actions::{
+        action::{self, Action},
+        action_type::ActionType,
+        action_validator, actions_finder,
+    },
+    game_entities::{
+        coord::Coord,
+        game_state::{GameState, MY_ID, OPPONENT_ID},
+        organ,
+        organ_type::OrganType,
+    },
+    simulation::{
+        evaluation::{self, EvaluationParams},
+        turn_simulator,
+    },
+};
+
+use super::{
+    opponent_model::{OpponentModel, MIN_OBSERVED_TURNS},
+    strategy::Strategy,
+    time_manager::TimeManager,
+};
+
+pub const NAME: &str = "genetic";
+
+/// Actions of every root of one player, one `Vec` per simulated turn.
+pub type Genome = Vec<Vec<Action>>;
+
+#[derive(Debug, Clone)]
+pub struct GeneticConfig {
+    pub population_size: usize,
+    pub opponent_population_size: usize,
+    /// Number of simulated turns.
+    pub depth: usize,
+    pub elite_count: usize,
+    pub tournament_size: usize,
+    /// Probability for each gene to be mutated.
+    pub mutation_rate: f64,
+    /// Opponent genomes each genome is simulated against.
+    pub opponents_per_evaluation: usize,
+    pub evaluation: EvaluationParams,
+    pub seed: u64,
+}
+
+impl Default for GeneticConfig {
+    fn default() -> Self {
+        GeneticConfig {
+            population_size: 20,
+            opponent_population_size: 10,
+            depth: 5,
+            elite_count: 2,
+            tournament_size: 3,
+            mutation_rate: 0.1,
+            opponents_per_evaluation: 3,
+            evaluation: EvaluationParams::default(),
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Individual {
+    genome: Genome,
+    fitness: f64,
+}
+
+impl Individual {
+    /// Ranked last until it is evaluated, the search may stop before.
+    fn new(genome: Genome) -> Self {
+        Individual {
+            genome,
+            fitness: f64::NEG_INFINITY,
+        }
+    }
+}
+
+/// Co-evolves our genomes with the opponent ones, so the best genome is robust to its replies.
+pub struct GeneticAlgorithm {
+    config: GeneticConfig,
+    rng: StdRng,
+    /// Populations of the last search, shifted one turn to start the next one.
+    population: Vec<Individual>,
+    opponents: Vec<Individual>,
+    /// Draws the random genes of `OPPONENT_ID` once it has observed enough turns.
+    opponent_model: Option<OpponentModel>,
+}
+
+impl GeneticAlgorithm {
+    pub fn new(config: GeneticConfig) -> Self {
+        let rng = StdRng::seed_from_u64(config.seed);
+        GeneticAlgorithm {
+            config,
+            rng,
+            population: Vec::new(),
+            opponents: Vec::new(),
+            opponent_model: None,
+        }
+    }
+
+    pub fn get_config(&self) -> &GeneticConfig {
+        &self.config
+    }
+
+    pub fn set_opponent_model(&mut self, opponent_model: OpponentModel) {
+        self.opponent_model = Some(opponent_model);
+    }
+
+    /// Best genome of `owner` found before `time` runs out.
+    pub fn search(&mut self, state: &GameState, owner: u8, time: &TimeManager) -> Genome {
+        let opponent = 1 - owner;
+        let (size, opponent_size) = (
+            self.config.population_size,
+            self.config.opponent_population_size,
+        );
+        let previous = std::mem::take(&mut self.population);
+        let mut population = self.start_population(state, owner, previous, size, time);
+        let previous = std::mem::take(&mut self.opponents);
+        let mut opponents = self.start_population(state, opponent, previous, opponent_size, time);
+        while self.evaluate(state, owner, &mut population, &opponents, time)
+            && self.evaluate(state, opponent, &mut opponents, &population, time)
+        {
+            population = self.next_generation(state, owner, &population, size);
+            opponents = self.next_generation(state, opponent, &opponents, opponent_size);
+        }
+        let best = population
+            .iter()
+            .max_by(|a, b| a.fitness.total_cmp(&b.fitness))
+            .map(|individual| individual.genome.clone())
+            .unwrap_or_default();
+        self.population = population;
+        self.opponents = opponents;
+        best
+    }
+
+    /// The previous population shifted one turn, completed with random genomes up to `size`.
+    /// Each genome simulates `depth` turns, so it stops with at least one genome once `time`
+    /// runs out and `next_generation` fills the rest.
+    fn start_population(
+        &mut self,
+        state: &GameState,
+        owner: u8,
+        previous: Vec<Individual>,
+        size: usize,
+        time: &TimeManager,
+    ) -> Vec<Individual> {
+        let mut population = Vec::with_capacity(size);
+        while population.len() < size {
+            let genome = match previous.get(population.len()) {
+                Some(individual) => self.shift_genome(state, owner, &individual.genome),
+                None => self.random_genome(state, owner),
+            };
+            population.push(Individual::new(genome));
+            if time.should_stop() {
+                break;
+            }
+        }
+        population
+    }
+
+    /// Drops the turn just played and draws a new last turn, the genes are kept by root
+    /// index, so a new root only shifts the genes a later mutation repairs.
+    pub fn shift_genome(&mut self, state: &GameState, owner: u8, genome: &Genome) -> Genome {
+        let roots = state.get_roots_in_order(owner);
+        let mut shifted: Genome = genome
+            .iter()
+            .skip(1)
+            .map(|genes| {
+                let mut genes = genes.clone();
+                genes.resize(roots.len(), action::wait());
+                genes
+            })
+            .collect();
+        let mut simulated = state.clone();
+        for genes in shifted.iter() {
+            play_turn(&mut simulated, owner, genes, &[]);
+        }
+        while shifted.len() < self.config.depth {
+            let genes = self.random_genes(&simulated, owner, &roots);
+            play_turn(&mut simulated, owner, &genes, &[]);
+            shifted.push(genes);
+        }
+        shifted
+    }
+
+    /// Random legal actions, drawn while the opponent waits.
+    pub fn random_genome(&mut self, state: &GameState, owner: u8) -> Genome {
+        let roots = state.get_roots_in_order(owner);
+        let mut simulated = state.clone();
+        let mut genome = Vec::with_capacity(self.config.depth);
+        for _ in 0..self.config.depth {
+            let genes = self.random_genes(&simulated, owner, &roots);
+            play_turn(&mut simulated, owner, &genes, &[]);
+            genome.push(genes);
+        }
+        genome
+    }
+
+    /// One random legal action per root of `roots`, WAIT for the dead ones.
+    fn random_genes(&mut self, state: &GameState, owner: u8, roots: &[Coord]) -> Vec<Action> {
+        let player = &state.players[owner as usize];
+        roots
+            .iter()
+            .map(|&root| {
+                if !player.get_roots().contains(&root) {
+                    return action::wait();
+                }
+                match self.opponent_model.as_ref() {
+                    Some(model)
+                        if owner == OPPONENT_ID && model.get_nb_turns() >= MIN_OBSERVED_TURNS =>
+                    {
+                        model.sample_action(state, owner, root, &mut self.rng)
+                    }
+                    _ => {
+                        let actions = actions_finder::find_all_actions(&state.grid, player, root);
+                        self.pick_action(&actions)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Prefers any move to WAIT, which is always the first action.
+    fn pick_action(&mut self, actions: &[Action]) -> Action {
+        if actions.len() > 1 && self.rng.gen_bool(0.9) {
+            actions[1..].choose(&mut self.rng).copied().unwrap()
+        } else {
+            action::wait()
+        }
+    }
+
+    /// False when `time` ran out before the whole population was evaluated.
+    fn evaluate(
+        &mut self,
+        state: &GameState,
+        owner: u8,
+        population: &mut [Individual],
+        opponents: &[Individual],
+        time: &TimeManager,
+    ) -> bool {
+        let count = self
+            .config
+            .opponents_per_evaluation
+            .min(opponents.len())
+            .max(1);
+        for individual in population.iter_mut() {
+            let mut total = 0.0;
+            for opponent in opponents.choose_multiple(&mut self.rng, count) {
+                total += play_out(
+                    state,
+                    owner,
+                    &individual.genome,
+                    &opponent.genome,
+                    &self.config.evaluation,
+                );
+            }
+            individual.fitness = total / count as f64;
+            if time.should_stop() {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn next_generation(
+        &mut self,
+        state: &GameState,
+        owner: u8,
+        population: &[Individual],
+        size: usize,
+    ) -> Vec<Individual> {
+        let mut sorted = population.to_vec();
+        sorted.sort_by(|a, b| b.fitness.total_cmp(&a.fitness));
+        let mut next: Vec<Individual> = sorted
+            .iter()
+            .take(self.config.elite_count)
+            .cloned()
+            .collect();
+        while next.len() < size {
+            let father = self.tournament(population);
+            let mother = self.tournament(population);
+            let mut genome = self.crossover(&father.genome, &mother.genome);
+            self.mutate(state, owner, &mut genome);
+            next.push(Individual::new(genome));
+        }
+        next
+    }
+
+    fn tournament<'a>(&mut self, population: &'a [Individual]) -> &'a Individual {
+        population
+            .choose_multiple(&mut self.rng, self.config.tournament_size.max(1))
+            .max_by(|a, b| a.fitness.total_cmp(&b.fitness))
+            .unwrap()
+    }
+
+    /// One point crossover on the turns.
+    pub fn crossover(&mut self, father: &Genome, mother: &Genome) -> Genome {
+        let cut = self.rng.gen_range(0..=father.len().min(mother.len()));
+        father[..cut]
+            .iter()
+            .chain(mother[cut.min(mother.len())..].iter())
+            .cloned()
+            .collect()
+    }
+
+    /// Replays `genome` and repairs the mutated genes on the grid they will be played on.
+    pub fn mutate(&mut self, state: &GameState, owner: u8, genome: &mut Genome) {
+        let mut simulated = state.clone();
+        let roots = state.get_roots_in_order(owner);
+        let mut last_sporer_growth: Option<Action> = None;
+        for genes in genome.iter_mut() {
+            for (gene, &root) in genes.iter_mut().zip(roots.iter()) {
+                if self.rng.gen_bool(self.config.mutation_rate) {
+                    *gene = self.mutate_gene(&simulated, owner, root, *gene, last_sporer_growth);
+                }
+                if action::get_type(*gene) == ActionType::Growth
+                    && action::get_organ_type(*gene) == OrganType::Sporer
+                {
+                    last_sporer_growth = Some(*gene);
+                }
+            }
+            play_turn(&mut simulated, owner, genes, &[]);
+        }
+    }
+
+    fn mutate_gene(
+        &mut self,
+        state: &GameState,
+        owner: u8,
+        root: Coord,
+        gene: Action,
+        last_sporer_growth: Option<Action>,
+    ) -> Action {
+        if !state.players[owner as usize].get_roots().contains(&root) {
+            return action::wait();
+        }
+        if action::get_type(gene) == ActionType::Wait {
+            let player = &state.players[owner as usize];
+            let actions = actions_finder::find_all_actions(&state.grid, player, root);
+            return self.pick_action(&actions);
+        }
+        if let Some(sporer_growth) = last_sporer_growth {
+            if action::get_coord_source(sporer_growth) == root && self.rng.gen_bool(0.5) {
+                let spore = action::sporer(
+                    action::get_direction(sporer_growth),
+                    action::get_coord_target(gene),
+                    root,
+                );
+                return action_validator::make_sporer_valid(
+                    sporer_growth,
+                    spore,
+                    &state.grid,
+                    &mut self.rng,
+                );
+            }
+        }
+        let direction = *actions_finder::DIRECTIONS.choose(&mut self.rng).unwrap();
+        let organ_type = *actions_finder::GROWABLE_TYPES
+            .choose(&mut self.rng)
+            .unwrap();
+        let growth = action::growth(organ_type, direction, action::get_coord_target(gene), root);
+        action_validator::make_growth_valid(growth, &state.grid, owner, &mut self.rng)
+    }
+}
+
+/// Plays one turn where `owner` plays `genes` and the other player `other_genes`.
+fn play_turn(state: &mut GameState, owner: u8, genes: &[Action], other_genes: &[Action]) {
+    let mut actions: [Vec<Action>; 2] = [Vec::new(), Vec::new()];
+    actions[owner as usize] = genes.to_vec();
+    actions[1 - owner as usize] = other_genes.to_vec();
+    turn_simulator::simulate_turn(state, &actions);
+}
+
+/// Evaluation for `owner` after playing both genomes.
+fn play_out(
+    state: &GameState,
+    owner: u8,
+    genome: &Genome,
+    opponent_genome: &Genome,
+    params: &EvaluationParams,
+) -> f64 {
+    let mut simulated = state.clone();
+    for turn in 0..genome.len().max(opponent_genome.len()) {
+        if turn_simulator::is_game_over(&simulated) {
+            break;
+        }
+        let genes = genome.get(turn).map(Vec::as_slice).unwrap_or(&[]);
+        let other_genes = opponent_genome.get(turn).map(Vec::as_slice).unwrap_or(&[]);
+        play_turn(&mut simulated, owner, genes, other_genes);
+    }
+    evaluation::get_score(&simulated, owner, params)
+}
+
+pub struct GeneticStrategy {
+    algorithm: GeneticAlgorithm,
+}
+
+impl GeneticStrategy {
+    pub fn new(config: GeneticConfig) -> Self {
+        GeneticStrategy {
+            algorithm: GeneticAlgorithm::new(config),
+        }
+    }
+}
+
+impl Default for GeneticStrategy {
+    fn default() -> Self {
+        GeneticStrategy::new(GeneticConfig::default())
+    }
+}
+
+impl Strategy for GeneticStrategy {
+    fn get_name(&self) -> &'static str {
+        NAME
+    }
+
+    fn observe_opponent(&mut self, model: &OpponentModel) {
+        self.algorithm.set_opponent_model(model.clone());
+    }
+
+    /// First turn of the best genome, with WAIT for the genes that cannot be played.
+    fn play(&mut self, state: &GameState, time: &TimeManager) -> Vec<Action> {
+        let genome = self.algorithm.search(state, MY_ID, time);
+        let first_turn = genome.into_iter().next().unwrap_or_default();
+        (0..state.get_roots_in_order(MY_ID).len())
+            .map(|index| match first_turn.get(index) {
+                Some(&gene) if is_playable(state, gene) => gene,
+                _ => action::wait(),
+            })
+            .collect()
+    }
+}
+
+fn is_playable(state: &GameState, gene: Action) -> bool {
+    match turn_simulator::get_placed_organ(&state.grid, MY_ID, gene) {
+        Some(organ) => state.get_me().can_buy(organ::get_type(organ)),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        game_entities::{
+            coord,
+            game_state::{wallet_from_counts, Entity, OPPONENT_ID},
+            protein_wallet,
+        },
+        strategies::bot_memory::InferredTurn,
+    };
+
+    use super::*;
+
+    fn build_state() -> GameState {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "7 1 ROOT 0 2 N 0 2",
+            "3 0 A -1 0 X 0 0",
+            "4 2 B -1 0 X 0 0",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(
+            8,
+            3,
+            &entities,
+            [
+                wallet_from_counts(5, 5, 5, 5),
+                wallet_from_counts(5, 5, 5, 5),
+            ],
+            1,
+        )
+    }
+
+    fn fast_time() -> TimeManager {
+        TimeManager::with_budget(Duration::from_millis(5), Duration::ZERO)
+    }
+
+    #[test]
+    fn test_random_genome_shape_and_first_turn_validity() {
+        let state = build_state();
+        let mut algorithm = GeneticAlgorithm::new(GeneticConfig::default());
+        let genome = algorithm.random_genome(&state, MY_ID);
+
+        assert_eq!(genome.len(), algorithm.get_config().depth);
+        assert!(genome.iter().all(|genes| genes.len() == 1));
+        assert!(action::is_valid_with_root(
+            genome[0][0],
+            &state.grid,
+            state.get_me()
+        ));
+    }
+
+    #[test]
+    fn test_opponent_genes_are_sampled_from_the_model() {
+        let state = build_state();
+        let idle_turn = InferredTurn {
+            turn: 1,
+            new_organs: Vec::new(),
+            spent: protein_wallet::new(),
+            income: [0; 4],
+            missed_actions: 0,
+        };
+        let mut algorithm = GeneticAlgorithm::new(GeneticConfig::default());
+        algorithm.set_opponent_model(OpponentModel::from_history(&vec![idle_turn; 20]));
+        let roots = [coord::new(7, 1)];
+
+        assert_eq!(
+            algorithm.random_genes(&state, OPPONENT_ID, &roots),
+            vec![action::wait()]
+        );
+    }
+
+    #[test]
+    fn test_crossover_keeps_depth() {
+        let state = build_state();
+        let mut algorithm = GeneticAlgorithm::new(GeneticConfig::default());
+        let father = algorithm.random_genome(&state, MY_ID);
+        let mother = algorithm.random_genome(&state, MY_ID);
+
+        for _ in 0..10 {
+            let child = algorithm.crossover(&father, &mother);
+            assert_eq!(child.len(), father.len());
+            assert!(child
+                .iter()
+                .enumerate()
+                .all(|(turn, genes)| genes == &father[turn] || genes == &mother[turn]));
+        }
+    }
+
+    #[test]
+    fn test_mutate_keeps_roots() {
+        let state = build_state();
+        let mut algorithm = GeneticAlgorithm::new(GeneticConfig {
+            mutation_rate: 1.0,
+            ..GeneticConfig::default()
+        });
+        let mut genome = algorithm.random_genome(&state, MY_ID);
+        algorithm.mutate(&state, MY_ID, &mut genome);
+
+        assert!(genome.iter().flatten().all(|&gene| {
+            action::get_type(gene) == ActionType::Wait
+                || action::get_coord_source(gene) == coord::new(0, 1)
+        }));
+    }
+
+    #[test]
+    fn test_shift_genome_moves_one_turn_forward() {
+        let state = build_state();
+        let mut algorithm = GeneticAlgorithm::new(GeneticConfig::default());
+        let genome = algorithm.random_genome(&state, MY_ID);
+        let shifted = algorithm.shift_genome(&state, MY_ID, &genome);
+
+        assert_eq!(shifted.len(), genome.len());
+        assert_eq!(shifted[..genome.len() - 1], genome[1..]);
+    }
+
+    #[test]
+    fn test_search_keeps_the_population() {
+        let state = build_state();
+        let mut algorithm = GeneticAlgorithm::new(GeneticConfig::default());
+        algorithm.search(&state, MY_ID, &fast_time());
+
+        assert_eq!(
+            algorithm.population.len(),
+            algorithm.get_config().population_size
+        );
+        let previous = algorithm.population[0].genome.clone();
+        let population = algorithm.start_population(
+            &state,
+            MY_ID,
+            algorithm.population.clone(),
+            algorithm.get_config().population_size,
+            &TimeManager::new(),
+        );
+        assert_eq!(population[0].genome[0], previous[1]);
+    }
+
+    #[test]
+    fn test_play_out_is_antisymmetric() {
+        let state = build_state();
+        let mut algorithm = GeneticAlgorithm::new(GeneticConfig::default());
+        let mine = algorithm.random_genome(&state, MY_ID);
+        let theirs = algorithm.random_genome(&state, OPPONENT_ID);
+
+        let params = EvaluationParams::default();
+        let score = play_out(&state, MY_ID, &mine, &theirs, &params);
+        assert_eq!(
+            play_out(&state, OPPONENT_ID, &theirs, &mine, &params),
+            -score
+        );
+    }
+
+    #[test]
+    fn test_search_respects_time_budget() {
+        let state = build_state();
+        let mut algorithm = GeneticAlgorithm::new(GeneticConfig::default());
+        let time = fast_time();
+        let genome = algorithm.search(&state, MY_ID, &time);
+
+        assert!(time.elapsed() < Duration::from_millis(500));
+        assert_eq!(genome.len(), algorithm.get_config().depth);
+    }
+
+    #[test]
+    fn test_search_checks_the_time_per_individual() {
+        let state = build_state();
+        let mut algorithm = GeneticAlgorithm::new(GeneticConfig::default());
+        let config = algorithm.get_config();
+        // one check per built genome of both populations, then one per evaluated individual
+        let checks = (config.population_size + config.opponent_population_size) as u32 + 2;
+        algorithm.search(&state, MY_ID, &TimeManager::with_max_checks(checks));
+
+        let evaluated = algorithm
+            .population
+            .iter()
+            .filter(|individual| individual.fitness.is_finite())
+            .count();
+        assert_eq!(evaluated, 2);
+    }
+
+    #[test]
+    fn test_population_is_built_within_the_time_budget() {
+        let state = build_state();
+        let mut algorithm = GeneticAlgorithm::new(GeneticConfig::default());
+        algorithm.search(&state, MY_ID, &TimeManager::with_max_checks(3));
+        let size = algorithm.get_config().population_size;
+
+        assert_eq!(algorithm.population.len(), 3);
+        algorithm.search(&state, MY_ID, &fast_time());
+        assert_eq!(algorithm.population.len(), size);
+    }
+
+    #[test]
+    fn test_strategy_plays_valid_actions() {
+        let state = build_state();
+        let mut strategy = GeneticStrategy::new(GeneticConfig::default());
+        let actions = strategy.play(&state, &fast_time());
+
+        assert_eq!(actions.len(), 1);
+        assert!(action::is_valid_with_root(
+            actions[0],
+            &state.grid,
+            state.get_me()
+        ));
+    }
+}
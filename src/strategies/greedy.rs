@@ -0,0 +1,46 @@
+use crate::{
+    actions::{action::Action, actions_coordinator},
+    game_entities::game_state::{GameState, MY_ID},
+};
+
+use super::{play, strategy::Strategy, time_manager::TimeManager};
+
+pub const NAME: &str = "greedy";
+
+/// Baseline: `play::found_an_action` for each root, without any lookahead,
+/// the roots sharing the wallet in root id order.
+#[derive(Default)]
+pub struct GreedyStrategy;
+
+impl Strategy for GreedyStrategy {
+    fn get_name(&self) -> &'static str {
+        NAME
+    }
+
+    fn play(&mut self, state: &GameState, _time: &TimeManager) -> Vec<Action> {
+        actions_coordinator::make_consistent(state, MY_ID, &play::found_actions(state, MY_ID))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::game_state::{wallet_from_counts, Entity};
+
+    use super::*;
+
+    #[test]
+    fn test_one_action_per_root() {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "4 1 ROOT 1 2 N 0 2",
+            "8 1 ROOT 0 3 N 0 3",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        let state =
+            GameState::from_entities(10, 3, &entities, [0, wallet_from_counts(5, 5, 5, 5)], 2);
+
+        assert_eq!(GreedyStrategy.play(&state, &TimeManager::new()).len(), 2);
+    }
+}
@@ -0,0 +1,564 @@
+use std::collections::HashMap;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{
+    actions::{
+        action::{self, Action},
+        actions_finder,
+    },
+    game_entities::{
+        coord::Coord,
+        game_state::{GameState, MY_ID},
+        grid::Grid,
+        organ::Organ,
+    },
+    simulation::{
+        evaluation::{self, EvaluationParams},
+        turn_simulator,
+    },
+};
+
+use super::{
+    bot_memory::InferredTurn,
+    opponent_model::{OpponentModel, MIN_OBSERVED_TURNS},
+    play,
+    strategy::Strategy,
+    time_manager::TimeManager,
+};
+
+pub const NAME: &str = "mcts";
+
+#[derive(Debug, Clone)]
+pub struct MctsConfig {
+    /// UCB1 exploration constant.
+    pub exploration: f64,
+    /// Greedy turns played after a new node.
+    pub rollout_depth: usize,
+    /// Candidate actions kept per root when a node is expanded.
+    pub actions_per_root: usize,
+    /// Evaluation giving a value of about 0.88.
+    pub value_scale: f64,
+    pub evaluation: EvaluationParams,
+    pub seed: u64,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        MctsConfig {
+            exploration: 1.4,
+            rollout_depth: 4,
+            actions_per_root: 8,
+            value_scale: 5.0,
+            evaluation: EvaluationParams::default(),
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct MoveStats {
+    visits: u32,
+    total_value: f64,
+}
+
+/// One action per root of a player.
+type Move = Vec<Action>;
+
+/// Decoupled node: each player picks its move from its own statistics, the pair gives the child.
+struct Node {
+    moves: [Vec<Move>; 2],
+    stats: [Vec<MoveStats>; 2],
+    children: HashMap<(usize, usize), usize>,
+    visits: u32,
+}
+
+impl Node {
+    fn new(state: &GameState, config: &MctsConfig, rng: &mut StdRng) -> Self {
+        let moves = [0u8, 1u8].map(|owner| generate_moves(state, owner, config, rng));
+        let stats = [
+            vec![MoveStats::default(); moves[0].len()],
+            vec![MoveStats::default(); moves[1].len()],
+        ];
+        Node {
+            moves,
+            stats,
+            children: HashMap::new(),
+            visits: 0,
+        }
+    }
+
+    fn select(&self, owner: u8, exploration: f64) -> usize {
+        let log_visits = (self.visits.max(1) as f64).ln();
+        let ucb = |stats: &MoveStats| {
+            if stats.visits == 0 {
+                return f64::INFINITY;
+            }
+            let visits = stats.visits as f64;
+            stats.total_value / visits + exploration * (log_visits / visits).sqrt()
+        };
+        let stats = &self.stats[owner as usize];
+        (0..stats.len())
+            .max_by(|&a, &b| ucb(&stats[a]).total_cmp(&ucb(&stats[b])))
+            .unwrap()
+    }
+}
+
+/// Greedy move first, then moves changing the action of a single root.
+fn generate_moves(
+    state: &GameState,
+    owner: u8,
+    config: &MctsConfig,
+    rng: &mut StdRng,
+) -> Vec<Move> {
+    let greedy = play::found_actions(state, owner);
+    let mut moves = vec![greedy.clone()];
+    let player = &state.players[owner as usize];
+    for (index, root) in state.get_roots_in_order(owner).into_iter().enumerate() {
+        let mut actions = actions_finder::find_all_actions(&state.grid, player, root);
+        actions.shuffle(rng);
+        for candidate in actions.into_iter().take(config.actions_per_root) {
+            if candidate != greedy[index] {
+                let mut variant = greedy.clone();
+                variant[index] = candidate;
+                moves.push(variant);
+            }
+        }
+    }
+    moves
+}
+
+/// Decoupled UCT over the simultaneous moves, anytime within the time budget.
+pub struct Mcts {
+    config: MctsConfig,
+    rng: StdRng,
+    nodes: Vec<Node>,
+    /// State the tree was searched from, to re-root it on the next turn.
+    root_state: Option<GameState>,
+    /// What the opponent did since the last search and the actions we printed then.
+    last_turn: Option<(InferredTurn, Vec<Action>)>,
+    /// Plays the opponent in the rollouts once it has seen enough turns.
+    opponent_model: Option<OpponentModel>,
+}
+
+impl Mcts {
+    pub fn new(config: MctsConfig) -> Self {
+        let rng = StdRng::seed_from_u64(config.seed);
+        Mcts {
+            config,
+            rng,
+            nodes: Vec::new(),
+            root_state: None,
+            last_turn: None,
+            opponent_model: None,
+        }
+    }
+
+    pub fn get_config(&self) -> &MctsConfig {
+        &self.config
+    }
+
+    pub fn get_nb_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn set_opponent_model(&mut self, opponent_model: OpponentModel) {
+        self.opponent_model = Some(opponent_model);
+    }
+
+    pub fn set_last_turn(&mut self, inferred: InferredTurn, our_actions: Vec<Action>) {
+        self.last_turn = Some((inferred, our_actions));
+    }
+
+    /// Most visited move of `owner` once `time` runs out.
+    /// The subtree of the previous search matching `state` is kept.
+    pub fn search(&mut self, state: &GameState, owner: u8, time: &TimeManager) -> Vec<Action> {
+        if self.reuse_tree(state) == 0 {
+            self.nodes.clear();
+            self.nodes
+                .push(Node::new(state, &self.config, &mut self.rng));
+        }
+        self.root_state = Some(state.clone());
+        loop {
+            self.iterate(state);
+            if time.should_stop() {
+                break;
+            }
+        }
+        self.get_best_move(owner)
+    }
+
+    /// Re-roots the tree at the child of the previous root reached by the actions we printed
+    /// and the opponent move placing the organs inferred since, 0 nodes when there is none.
+    /// The child is checked by simulating that single pair of moves.
+    fn reuse_tree(&mut self, state: &GameState) -> usize {
+        let (previous, (inferred, our_actions)) =
+            match (self.root_state.take(), self.last_turn.take()) {
+                (Some(previous), Some(last_turn)) if !self.nodes.is_empty() => {
+                    (previous, last_turn)
+                }
+                _ => return 0,
+            };
+        let root = &self.nodes[0];
+        let mut inferred_organs: Vec<(Coord, Organ)> = inferred
+            .new_organs
+            .iter()
+            .map(|organ| (organ.coord, organ.organ))
+            .collect();
+        inferred_organs.sort_unstable();
+        let mine = root.moves[MY_ID as usize]
+            .iter()
+            .position(|our_move| *our_move == our_actions);
+        let theirs = root.moves[1 - MY_ID as usize]
+            .iter()
+            .position(|their_move| {
+                get_placed_organs(&previous.grid, 1 - MY_ID, their_move) == inferred_organs
+            });
+        let child = match (mine, theirs) {
+            (Some(mine), Some(theirs)) => {
+                let mut actions: [Vec<Action>; 2] = [Vec::new(), Vec::new()];
+                actions[MY_ID as usize] = our_actions;
+                actions[1 - MY_ID as usize] = root.moves[1 - MY_ID as usize][theirs].clone();
+                let mut simulated = previous;
+                turn_simulator::simulate_turn(&mut simulated, &actions);
+                root.children
+                    .get(&(mine, theirs))
+                    .copied()
+                    .filter(|_| is_same_position(&simulated, state))
+            }
+            _ => None,
+        };
+        match child {
+            Some(child) => {
+                self.keep_subtree(child);
+                self.nodes.len()
+            }
+            None => 0,
+        }
+    }
+
+    /// Keeps the nodes under `new_root` only, `new_root` becoming the first node.
+    fn keep_subtree(&mut self, new_root: usize) {
+        let mut old_nodes: Vec<Option<Node>> = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut new_indexes: HashMap<usize, usize> = HashMap::new();
+        let mut order = vec![new_root];
+        let mut next = 0;
+        while next < order.len() {
+            let index = order[next];
+            new_indexes.insert(index, next);
+            if let Some(node) = old_nodes[index].as_ref() {
+                order.extend(node.children.values().copied());
+            }
+            next += 1;
+        }
+        for index in order {
+            if let Some(mut node) = old_nodes[index].take() {
+                for child in node.children.values_mut() {
+                    *child = new_indexes[child];
+                }
+                self.nodes.push(node);
+            }
+        }
+    }
+
+    fn get_best_move(&self, owner: u8) -> Vec<Action> {
+        let root = &self.nodes[0];
+        let stats = &root.stats[owner as usize];
+        (0..stats.len())
+            .max_by_key(|&index| stats[index].visits)
+            .map(|index| root.moves[owner as usize][index].clone())
+            .unwrap_or_default()
+    }
+
+    fn iterate(&mut self, state: &GameState) {
+        let mut simulated = state.clone();
+        let mut path: Vec<(usize, usize, usize)> = Vec::new();
+        let mut node_index = 0;
+        loop {
+            if turn_simulator::is_game_over(&simulated) {
+                break;
+            }
+            let node = &self.nodes[node_index];
+            let mine = node.select(MY_ID, self.config.exploration);
+            let theirs = node.select(1 - MY_ID, self.config.exploration);
+            let mut actions: [Vec<Action>; 2] = [Vec::new(), Vec::new()];
+            actions[MY_ID as usize] = node.moves[MY_ID as usize][mine].clone();
+            actions[1 - MY_ID as usize] = node.moves[1 - MY_ID as usize][theirs].clone();
+            let child = node.children.get(&(mine, theirs)).copied();
+            turn_simulator::simulate_turn(&mut simulated, &actions);
+            path.push((node_index, mine, theirs));
+            match child {
+                Some(child) => node_index = child,
+                None => {
+                    let child = Node::new(&simulated, &self.config, &mut self.rng);
+                    self.nodes.push(child);
+                    let child_index = self.nodes.len() - 1;
+                    self.nodes[node_index]
+                        .children
+                        .insert((mine, theirs), child_index);
+                    break;
+                }
+            }
+        }
+        let value = self.rollout(&mut simulated);
+        for (index, mine, theirs) in path {
+            let node = &mut self.nodes[index];
+            node.visits += 1;
+            let my_stats = &mut node.stats[MY_ID as usize][mine];
+            my_stats.visits += 1;
+            my_stats.total_value += value;
+            let their_stats = &mut node.stats[1 - MY_ID as usize][theirs];
+            their_stats.visits += 1;
+            their_stats.total_value += 1.0 - value;
+        }
+    }
+
+    /// Greedy play for us, the opponent model or greedy play for the opponent,
+    /// the value is ours and lies in [0, 1].
+    fn rollout(&mut self, state: &mut GameState) -> f64 {
+        for _ in 0..self.config.rollout_depth {
+            if turn_simulator::is_game_over(state) {
+                break;
+            }
+            let mut actions = [Vec::new(), Vec::new()];
+            actions[MY_ID as usize] = play::found_actions(state, MY_ID);
+            actions[1 - MY_ID as usize] = match self.opponent_model.as_ref() {
+                Some(model) if model.get_nb_turns() >= MIN_OBSERVED_TURNS => {
+                    model.sample_actions(state, 1 - MY_ID, &mut self.rng)
+                }
+                _ => play::found_actions(state, 1 - MY_ID),
+            };
+            turn_simulator::simulate_turn(state, &actions);
+        }
+        let score = evaluation::get_score(state, MY_ID, &self.config.evaluation);
+        (1.0 + (score / self.config.value_scale).tanh()) / 2.0
+    }
+}
+
+/// Organs `one_move` of `owner` places on `grid`, sorted, whether they are affordable or not.
+fn get_placed_organs(grid: &Grid, owner: u8, one_move: &[Action]) -> Vec<(Coord, Organ)> {
+    let mut organs: Vec<(Coord, Organ)> = one_move
+        .iter()
+        .filter_map(|&candidate| {
+            turn_simulator::get_placed_organ(grid, owner, candidate)
+                .map(|organ| (action::get_coord_target(candidate), organ))
+        })
+        .collect();
+    organs.sort_unstable();
+    organs
+}
+
+/// Same cells and wallets, so same moves and values.
+fn is_same_position(a: &GameState, b: &GameState) -> bool {
+    a.grid.width == b.grid.width
+        && a.grid.height == b.grid.height
+        && a.grid
+            .iter_coords()
+            .all(|coord| a.grid.get_cell_from_coord(coord) == b.grid.get_cell_from_coord(coord))
+        && (0..2).all(|owner| a.players[owner].get_wallet() == b.players[owner].get_wallet())
+}
+
+pub struct MctsStrategy {
+    mcts: Mcts,
+}
+
+impl MctsStrategy {
+    pub fn new(config: MctsConfig) -> Self {
+        MctsStrategy {
+            mcts: Mcts::new(config),
+        }
+    }
+}
+
+impl Default for MctsStrategy {
+    fn default() -> Self {
+        MctsStrategy::new(MctsConfig::default())
+    }
+}
+
+impl Strategy for MctsStrategy {
+    fn get_name(&self) -> &'static str {
+        NAME
+    }
+
+    fn observe_opponent(&mut self, model: &OpponentModel) {
+        self.mcts.set_opponent_model(model.clone());
+    }
+
+    fn observe_last_turn(&mut self, inferred: &InferredTurn, our_actions: &[Action]) {
+        self.mcts
+            .set_last_turn(inferred.clone(), our_actions.to_vec());
+    }
+
+    fn play(&mut self, state: &GameState, time: &TimeManager) -> Vec<Action> {
+        let best = self.mcts.search(state, MY_ID, time);
+        (0..state.get_roots_in_order(MY_ID).len())
+            .map(|index| best.get(index).copied().unwrap_or_else(action::wait))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::game_entities::{
+        coord,
+        game_state::{wallet_from_counts, Entity, OPPONENT_ID},
+    };
+
+    use super::{
+        super::bot_memory::{self, InferredTurn},
+        *,
+    };
+
+    fn build_state() -> GameState {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "1 1 BASIC 1 3 E 1 1",
+            "7 1 ROOT 0 2 N 0 2",
+            "3 0 A -1 0 X 0 0",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(
+            8,
+            3,
+            &entities,
+            [
+                wallet_from_counts(3, 3, 3, 3),
+                wallet_from_counts(3, 3, 3, 3),
+            ],
+            1,
+        )
+    }
+
+    fn fast_time() -> TimeManager {
+        TimeManager::with_budget(Duration::from_millis(5), Duration::ZERO)
+    }
+
+    #[test]
+    fn test_generate_moves_starts_with_greedy() {
+        let state = build_state();
+        let config = MctsConfig::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let moves = generate_moves(&state, MY_ID, &config, &mut rng);
+
+        assert_eq!(moves[0], play::found_actions(&state, MY_ID));
+        assert!(moves.len() <= 1 + config.actions_per_root);
+        assert!(moves.iter().all(|one_move| one_move.len() == 1));
+        assert_eq!(
+            generate_moves(&state, OPPONENT_ID, &config, &mut rng)[0],
+            play::found_actions(&state, OPPONENT_ID)
+        );
+    }
+
+    #[test]
+    fn test_search_grows_the_tree() {
+        let state = build_state();
+        let mut mcts = Mcts::new(MctsConfig::default());
+        let best = mcts.search(&state, MY_ID, &fast_time());
+
+        assert_eq!(best.len(), 1);
+        assert!(mcts.get_nb_nodes() > 1);
+        let root = &mcts.nodes[0];
+        let visits: u32 = root.stats[MY_ID as usize].iter().map(|s| s.visits).sum();
+        assert_eq!(visits, root.visits);
+    }
+
+    #[test]
+    fn test_rollout_value_is_bounded() {
+        let mut state = build_state();
+        let mut mcts = Mcts::new(MctsConfig::default());
+        let value = mcts.rollout(&mut state);
+
+        assert!((0.0..=1.0).contains(&value));
+        assert!(value > 0.5);
+    }
+
+    #[test]
+    fn test_reuse_the_played_branch() {
+        let state = build_state();
+        let mut mcts = Mcts::new(MctsConfig::default());
+        let best = mcts.search(&state, MY_ID, &fast_time());
+        let root = &mcts.nodes[0];
+        let (&(_, theirs), &child) = root
+            .children
+            .iter()
+            .filter(|(&(mine, _), _)| root.moves[MY_ID as usize][mine] == best)
+            .max_by_key(|(_, &child)| mcts.nodes[child].visits)
+            .unwrap();
+        let child_visits = mcts.nodes[child].visits;
+        let mut actions: [Vec<Action>; 2] = [Vec::new(), Vec::new()];
+        actions[MY_ID as usize] = best.clone();
+        actions[OPPONENT_ID as usize] = root.moves[OPPONENT_ID as usize][theirs].clone();
+        let mut next_state = state.clone();
+        turn_simulator::simulate_turn(&mut next_state, &actions);
+
+        mcts.set_last_turn(
+            bot_memory::infer_turn(&state, &next_state, &best),
+            best.clone(),
+        );
+
+        assert!(mcts.reuse_tree(&next_state) > 0);
+        assert_eq!(mcts.nodes[0].visits, child_visits);
+        assert!(mcts.nodes.iter().all(|node| node
+            .children
+            .values()
+            .all(|&index| index < mcts.nodes.len())));
+        // the tree was searched from next_state and cannot be re-rooted at state
+        mcts.root_state = Some(next_state.clone());
+        mcts.set_last_turn(bot_memory::infer_turn(&next_state, &state, &best), best);
+        assert_eq!(mcts.reuse_tree(&state), 0);
+    }
+
+    #[test]
+    fn test_reuse_needs_the_last_turn() {
+        let state = build_state();
+        let mut mcts = Mcts::new(MctsConfig::default());
+        let best = mcts.search(&state, MY_ID, &fast_time());
+        let mut actions: [Vec<Action>; 2] = [Vec::new(), Vec::new()];
+        actions[MY_ID as usize] = best;
+        let mut next_state = state.clone();
+        turn_simulator::simulate_turn(&mut next_state, &actions);
+
+        assert_eq!(mcts.reuse_tree(&next_state), 0);
+    }
+
+    #[test]
+    fn test_rollout_with_an_opponent_model() {
+        let mut state = build_state();
+        let mut mcts = Mcts::new(MctsConfig::default());
+        mcts.set_opponent_model(OpponentModel::from_history(&vec![
+            InferredTurn {
+                turn: 1,
+                new_organs: Vec::new(),
+                spent: 0,
+                income: [0; 4],
+                missed_actions: 0,
+            };
+            MIN_OBSERVED_TURNS
+        ]));
+        let value = mcts.rollout(&mut state);
+
+        // an opponent always waiting lets us lead
+        assert!(value > 0.5);
+    }
+
+    #[test]
+    fn test_strategy_plays_one_action_per_root() {
+        let state = build_state();
+        let mut strategy = MctsStrategy::new(MctsConfig::default());
+        let actions = strategy.play(&state, &fast_time());
+
+        assert_eq!(actions.len(), 1);
+        assert!(
+            action::get_coord_source(actions[0]) == coord::new(0, 1)
+                || actions[0] == action::wait()
+        );
+    }
+}
@@ -1 +1,18 @@
+pub mod attack_planner;
+pub mod beam_search;
+pub mod bot_memory;
+pub mod defense_planner;
+pub mod denial_planner;
+pub mod economy_planner;
+pub mod endgame_filler;
+pub mod genetic_algorithm;
+pub mod greedy;
+pub mod mcts;
+pub mod opponent_model;
+pub mod planner;
 pub mod play;
+pub mod registry;
+pub mod self_play;
+pub mod spore_planner;
+pub mod strategy;
+pub mod time_manager;
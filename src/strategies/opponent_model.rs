@@ -0,0 +1,327 @@
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+use crate::{
+    actions::{
+        action::{self, Action},
+        action_type::ActionType,
+        actions_finder,
+    },
+    game_entities::{coord::Coord, game_state::GameState, organ_type::OrganType},
+};
+
+use super::bot_memory::InferredTurn;
+
+/// Observations added to every count, so that an unseen behaviour stays possible.
+pub const PRIOR_COUNT: f64 = 1.0;
+
+/// Turns to observe before trusting the model over the greedy policy.
+pub const MIN_OBSERVED_TURNS: usize = 3;
+
+/// Tentacles per turn from which the opponent is aggressive.
+pub const AGGRESSIVE_TENTACLES_PER_TURN: f64 = 0.3;
+
+/// Share of harvesters from which the opponent plays for its economy.
+pub const ECONOMIC_HARVESTER_SHARE: f64 = 0.3;
+
+/// Organs per turn from which the opponent is expanding.
+pub const EXPANSIVE_ORGANS_PER_TURN: f64 = 1.5;
+
+const ORGAN_TYPES: [OrganType; 5] = [
+    OrganType::Root,
+    OrganType::Basic,
+    OrganType::Harvester,
+    OrganType::Sporer,
+    OrganType::Tentacle,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpponentStyle {
+    Aggressive,
+    Economic,
+    Expansive,
+    Balanced,
+}
+
+/// Statistics of the turns the opponent played, turned into action probabilities.
+#[derive(Debug, Clone, Default)]
+pub struct OpponentModel {
+    nb_turns: usize,
+    nb_idle_turns: usize,
+    /// Organs grown, indexed by `OrganType::get_index`.
+    type_counts: [usize; 5],
+}
+
+impl OpponentModel {
+    pub fn from_history(history: &[InferredTurn]) -> Self {
+        let mut model = OpponentModel::default();
+        for turn in history {
+            model.nb_turns += 1;
+            if turn.new_organs.is_empty() {
+                model.nb_idle_turns += 1;
+            }
+            for organ in turn.new_organs.iter() {
+                model.type_counts[organ.organ_type.get_index() as usize] += 1;
+            }
+        }
+        model
+    }
+
+    pub fn get_nb_turns(&self) -> usize {
+        self.nb_turns
+    }
+
+    fn get_nb_organs(&self) -> usize {
+        self.type_counts.iter().sum()
+    }
+
+    /// Smoothed share of the grown organs having `organ_type`.
+    pub fn get_type_frequency(&self, organ_type: OrganType) -> f64 {
+        (self.type_counts[organ_type.get_index() as usize] as f64 + PRIOR_COUNT)
+            / (self.get_nb_organs() as f64 + PRIOR_COUNT * ORGAN_TYPES.len() as f64)
+    }
+
+    /// Smoothed share of the turns where the opponent grew nothing.
+    pub fn get_idle_rate(&self) -> f64 {
+        (self.nb_idle_turns as f64 + PRIOR_COUNT) / (self.nb_turns as f64 + 2.0 * PRIOR_COUNT)
+    }
+
+    /// Tentacles grown per turn.
+    pub fn get_aggression(&self) -> f64 {
+        self.get_per_turn(self.type_counts[OrganType::Tentacle.get_index() as usize])
+    }
+
+    /// Organs grown per turn.
+    pub fn get_expansion(&self) -> f64 {
+        self.get_per_turn(self.get_nb_organs())
+    }
+
+    /// Share of the grown organs being harvesters.
+    pub fn get_economy_priority(&self) -> f64 {
+        let nb_organs = self.get_nb_organs();
+        if nb_organs == 0 {
+            return 0.0;
+        }
+        self.type_counts[OrganType::Harvester.get_index() as usize] as f64 / nb_organs as f64
+    }
+
+    fn get_per_turn(&self, count: usize) -> f64 {
+        if self.nb_turns == 0 {
+            return 0.0;
+        }
+        count as f64 / self.nb_turns as f64
+    }
+
+    pub fn classify(&self) -> OpponentStyle {
+        if self.get_aggression() >= AGGRESSIVE_TENTACLES_PER_TURN {
+            OpponentStyle::Aggressive
+        } else if self.get_economy_priority() >= ECONOMIC_HARVESTER_SHARE {
+            OpponentStyle::Economic
+        } else if self.get_expansion() >= EXPANSIVE_ORGANS_PER_TURN {
+            OpponentStyle::Expansive
+        } else {
+            OpponentStyle::Balanced
+        }
+    }
+
+    /// Probability of each legal action of the organism rooted at `root_coord`: WAIT with
+    /// the idle rate, then an organ type by frequency, uniformly among its actions.
+    pub fn get_action_probabilities(
+        &self,
+        state: &GameState,
+        owner: u8,
+        root_coord: Coord,
+    ) -> Vec<(Action, f64)> {
+        let player = &state.players[owner as usize];
+        let actions = actions_finder::find_all_actions(&state.grid, player, root_coord);
+        let mut counts = [0usize; 5];
+        for &candidate in actions.iter() {
+            if let Some(organ_type) = get_grown_type(candidate) {
+                counts[organ_type.get_index() as usize] += 1;
+            }
+        }
+        let total_frequency: f64 = ORGAN_TYPES
+            .iter()
+            .filter(|organ_type| counts[organ_type.get_index() as usize] > 0)
+            .map(|&organ_type| self.get_type_frequency(organ_type))
+            .sum();
+        let idle_rate = if total_frequency > 0.0 {
+            self.get_idle_rate()
+        } else {
+            1.0
+        };
+        actions
+            .into_iter()
+            .map(|candidate| {
+                let probability = match get_grown_type(candidate) {
+                    None => idle_rate,
+                    Some(organ_type) => {
+                        (1.0 - idle_rate) * self.get_type_frequency(organ_type)
+                            / total_frequency
+                            / counts[organ_type.get_index() as usize] as f64
+                    }
+                };
+                (candidate, probability)
+            })
+            .collect()
+    }
+
+    /// One sampled action per root of `owner`, in root order.
+    pub fn sample_actions(&self, state: &GameState, owner: u8, rng: &mut StdRng) -> Vec<Action> {
+        state
+            .get_roots_in_order(owner)
+            .into_iter()
+            .map(|root| self.sample_action(state, owner, root, rng))
+            .collect()
+    }
+
+    /// One action of the organism rooted at `root_coord`, drawn from the probabilities.
+    pub fn sample_action(
+        &self,
+        state: &GameState,
+        owner: u8,
+        root_coord: Coord,
+        rng: &mut StdRng,
+    ) -> Action {
+        self.get_action_probabilities(state, owner, root_coord)
+            .choose_weighted(rng, |&(_, probability)| probability)
+            .map(|&(candidate, _)| candidate)
+            .unwrap_or_else(|_| action::wait())
+    }
+}
+
+/// Type of the organ `candidate` places, ROOT for a SPORE, None for WAIT.
+fn get_grown_type(candidate: Action) -> Option<OrganType> {
+    match action::get_type(candidate) {
+        ActionType::Wait => None,
+        ActionType::Growth => Some(action::get_organ_type(candidate)),
+        ActionType::Sporer => Some(OrganType::Root),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::game_entities::{
+        cell_content::OrganEntity,
+        coord,
+        game_state::{wallet_from_counts, Entity, OPPONENT_ID},
+        organ,
+        organ_direction::OrganDirection,
+        protein_wallet,
+    };
+
+    use super::*;
+
+    fn turn_with(organ_types: &[OrganType]) -> InferredTurn {
+        InferredTurn {
+            turn: 1,
+            new_organs: organ_types
+                .iter()
+                .map(|&organ_type| {
+                    let coord = coord::new(0, 0);
+                    OrganEntity::new(
+                        coord,
+                        organ::new(OPPONENT_ID, organ_type, OrganDirection::North, coord),
+                    )
+                })
+                .collect(),
+            spent: protein_wallet::new(),
+            income: [0; 4],
+            missed_actions: 0,
+        }
+    }
+
+    #[test]
+    fn test_statistics() {
+        let history = [
+            turn_with(&[OrganType::Tentacle, OrganType::Harvester]),
+            turn_with(&[OrganType::Tentacle]),
+            turn_with(&[]),
+            turn_with(&[OrganType::Basic]),
+        ];
+        let model = OpponentModel::from_history(&history);
+
+        assert_eq!(model.get_nb_turns(), 4);
+        assert_eq!(model.get_aggression(), 0.5);
+        assert_eq!(model.get_expansion(), 1.0);
+        assert_eq!(model.get_economy_priority(), 0.25);
+        assert_eq!(model.get_idle_rate(), 2.0 / 6.0);
+        assert_eq!(model.get_type_frequency(OrganType::Tentacle), 3.0 / 9.0);
+        assert_eq!(model.classify(), OpponentStyle::Aggressive);
+    }
+
+    #[test]
+    fn test_classify() {
+        let economic = OpponentModel::from_history(&[
+            turn_with(&[OrganType::Harvester]),
+            turn_with(&[OrganType::Basic]),
+        ]);
+        let expansive =
+            OpponentModel::from_history(&[turn_with(&[OrganType::Basic, OrganType::Basic])]);
+
+        assert_eq!(economic.classify(), OpponentStyle::Economic);
+        assert_eq!(expansive.classify(), OpponentStyle::Expansive);
+        assert_eq!(OpponentModel::default().classify(), OpponentStyle::Balanced);
+    }
+
+    fn build_state() -> GameState {
+        let entities: Vec<Entity> = ["0 1 ROOT 1 1 N 0 1", "5 1 ROOT 0 2 N 0 2"]
+            .iter()
+            .map(|line| Entity::from_line(line))
+            .collect();
+        GameState::from_entities(
+            6,
+            3,
+            &entities,
+            [
+                wallet_from_counts(1, 1, 1, 1),
+                wallet_from_counts(0, 0, 0, 0),
+            ],
+            1,
+        )
+    }
+
+    #[test]
+    fn test_action_probabilities() {
+        let state = build_state();
+        let model = OpponentModel::from_history(&[
+            turn_with(&[OrganType::Tentacle]),
+            turn_with(&[OrganType::Tentacle]),
+        ]);
+        let probabilities = model.get_action_probabilities(&state, OPPONENT_ID, coord::new(5, 1));
+        let total: f64 = probabilities
+            .iter()
+            .map(|(_, probability)| probability)
+            .sum();
+        let tentacles: f64 = probabilities
+            .iter()
+            .filter(|(candidate, _)| get_grown_type(*candidate) == Some(OrganType::Tentacle))
+            .map(|(_, probability)| probability)
+            .sum();
+        let basics: f64 = probabilities
+            .iter()
+            .filter(|(candidate, _)| get_grown_type(*candidate) == Some(OrganType::Basic))
+            .map(|(_, probability)| probability)
+            .sum();
+
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(tentacles > 2.0 * basics);
+        assert_eq!(probabilities[0], (action::wait(), model.get_idle_rate()));
+    }
+
+    #[test]
+    fn test_sample_actions() {
+        let state = build_state();
+        let model = OpponentModel::from_history(&[turn_with(&[OrganType::Basic])]);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let actions = model.sample_actions(&state, OPPONENT_ID, &mut rng);
+        assert_eq!(actions.len(), 1);
+        // we have no protein, so only WAIT is possible
+        assert_eq!(
+            model.sample_actions(&state, 1, &mut rng),
+            vec![action::wait()]
+        );
+    }
+}
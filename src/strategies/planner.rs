@@ -0,0 +1,211 @@
+use crate::{
+    actions::{
+        action::{self, Action},
+        action_type::ActionType,
+        actions_coordinator, actions_finder,
+    },
+    game_entities::{
+        cell,
+        game_state::{GameState, MY_ID},
+        organ_type::OrganType,
+        protein_wallet,
+    },
+};
+
+use super::{
+    defense_planner::DefensePlanner,
+    denial_planner,
+    economy_planner::{EconomyPlanner, PurchaseGoal},
+    play,
+    spore_planner::SporePlanner,
+    strategy::Strategy,
+    time_manager::TimeManager,
+};
+
+pub const NAME: &str = "planner";
+
+/// The planner strategy keeps a TENTACLE affordable within this many turns.
+pub const GOALS: [PurchaseGoal; 1] = [PurchaseGoal {
+    organ_type: OrganType::Tentacle,
+    deadline: 5,
+}];
+
+/// Defenses protecting less are not worth a TENTACLE.
+pub const MIN_DEFENSE_VALUE: f64 = 3.0;
+
+/// Denials keeping less opponent income per protein spent are not worth it.
+pub const MIN_DENIAL_VALUE: f64 = 3.0;
+
+/// Spore plans scoring less are not worth a SPORER and a ROOT.
+pub const MIN_PLAN_SCORE: f64 = 5.0;
+
+/// The greedy baseline behind the economy, spore, defense and denial planners, the roots
+/// sharing the wallet through `actions_coordinator::optimise`. Compared against `greedy`
+/// to measure the planners.
+#[derive(Default)]
+pub struct PlannerStrategy;
+
+impl Strategy for PlannerStrategy {
+    fn get_name(&self) -> &'static str {
+        NAME
+    }
+
+    fn play(&mut self, state: &GameState, _time: &TimeManager) -> Vec<Action> {
+        let candidates = find_candidates(state, MY_ID, &GOALS);
+        actions_coordinator::optimise(state, MY_ID, &rank(&candidates))
+    }
+}
+
+/// Values decreasing with the position of each candidate, the first one being preferred.
+fn rank(candidates: &[Vec<Action>]) -> Vec<Vec<(Action, f64)>> {
+    candidates
+        .iter()
+        .map(|root_candidates| {
+            root_candidates
+                .iter()
+                .enumerate()
+                .map(|(index, &candidate)| (candidate, (root_candidates.len() - index) as f64))
+                .collect()
+        })
+        .collect()
+}
+
+/// Candidates of each root: the best defense, the spore of a grown sporer, the best
+/// denial, harvesters on the proteins the goals need, the sporer of the best spore plan,
+/// then the greedy action. Purchases must be allowed by the economy planner.
+fn find_candidates(state: &GameState, owner: u8, goals: &[PurchaseGoal]) -> Vec<Vec<Action>> {
+    let planner = EconomyPlanner::new(state, owner);
+    let needed = planner.get_needed_harvests(goals);
+    let allowed = planner.get_allowed_purchases(goals);
+    let mut spore_planner = SporePlanner::new(&state.grid, owner);
+    let defense_planner = DefensePlanner::new(&state.grid, owner);
+    let player = &state.players[owner as usize];
+    state
+        .get_roots_in_order(owner)
+        .into_iter()
+        .map(|root| {
+            let mut candidates: Vec<Action> = defense_planner
+                .find_proposals(&state.grid, root)
+                .first()
+                .filter(|proposal| proposal.value >= MIN_DEFENSE_VALUE)
+                .map(|proposal| proposal.action)
+                .into_iter()
+                .collect();
+            candidates.extend(
+                spore_planner
+                    .find_best_spore(&state.grid, player, root)
+                    .filter(|&(_, score)| score > 0.0)
+                    .map(|(spore, _)| spore),
+            );
+            candidates.extend(
+                denial_planner::find_denials(&state.grid, player, root)
+                    .first()
+                    .filter(|denial| {
+                        denial.value >= MIN_DENIAL_VALUE
+                            && allowed.contains(&action::get_organ_type(denial.action))
+                    })
+                    .map(|denial| denial.action),
+            );
+            candidates.extend(
+                actions_finder::find_growth_actions(&state.grid, player, root)
+                    .into_iter()
+                    .filter(|&candidate| {
+                        action::get_organ_type(candidate) == OrganType::Harvester
+                            && state
+                                .grid
+                                .get_next_coord(
+                                    action::get_coord_target(candidate),
+                                    action::get_direction(candidate),
+                                )
+                                .and_then(|faced| {
+                                    cell::get_protein(state.grid.get_cell_from_coord(faced))
+                                })
+                                .is_some_and(|protein| needed.contains(&protein))
+                    }),
+            );
+            if allowed.contains(&OrganType::Sporer) {
+                if let Some(plan) = spore_planner.find_plans(&state.grid, root).first() {
+                    if plan.score >= MIN_PLAN_SCORE
+                        && protein_wallet::can_pay(player.get_wallet(), plan.cost)
+                    {
+                        candidates.push(plan.sporer);
+                    }
+                }
+            }
+            let greedy = play::found_an_action(
+                &state.grid,
+                player,
+                &state.players[1 - owner as usize],
+                root,
+            );
+            if action::get_type(greedy) != ActionType::Growth
+                || allowed.contains(&action::get_organ_type(greedy))
+            {
+                candidates.push(greedy);
+            }
+            candidates
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_entities::{
+        coord,
+        game_state::{wallet_from_counts, Entity},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_one_action_per_root() {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "4 1 ROOT 1 2 N 0 2",
+            "8 1 ROOT 0 3 N 0 3",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        let state =
+            GameState::from_entities(10, 3, &entities, [0, wallet_from_counts(5, 5, 5, 5)], 2);
+
+        assert_eq!(PlannerStrategy.play(&state, &TimeManager::new()).len(), 2);
+    }
+
+    #[test]
+    fn test_rank_prefers_the_first_candidates() {
+        let candidates = vec![vec![action::wait(), 7], vec![]];
+
+        assert_eq!(
+            rank(&candidates),
+            vec![vec![(action::wait(), 2.0), (7, 1.0)], vec![]]
+        );
+    }
+
+    #[test]
+    fn test_harvests_the_protein_a_goal_needs() {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "2 1 D -1 0 X 0 0",
+            "6 1 ROOT 0 3 N 0 3",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        let state =
+            GameState::from_entities(7, 3, &entities, [0, wallet_from_counts(0, 1, 1, 0)], 1);
+        let actions = PlannerStrategy.play(&state, &TimeManager::new());
+
+        // no D income: the tentacle goal needs a harvester on the D source
+        assert_eq!(actions.len(), 1);
+        assert_eq!(action::get_organ_type(actions[0]), OrganType::Harvester);
+        assert_eq!(
+            state.grid.get_next_coord(
+                action::get_coord_target(actions[0]),
+                action::get_direction(actions[0])
+            ),
+            Some(coord::new(2, 1))
+        );
+    }
+}
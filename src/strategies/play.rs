@@ -5,6 +5,7 @@ use crate::{
     game_entities::{
         cell,
         coord::{self, Coord},
+        game_state::GameState,
         grid::Grid,
         organ,
         organ_direction::{self, OrganDirection},
@@ -13,18 +14,32 @@ use crate::{
     },
 };
 
+use super::attack_planner;
+
 // pub fn get_best_action(grid: &Grid, player: &Player, root_coord: Coord) -> Action {}
 
-pub fn found_an_action(grid: &Grid, player: &Player, root_coord: Coord) -> Action {
+pub fn found_an_action(
+    grid: &Grid,
+    player: &Player,
+    opponent: &Player,
+    root_coord: Coord,
+) -> Action {
+    if player.can_buy(OrganType::Tentacle) {
+        if let Some(attack) =
+            attack_planner::find_attacks(grid, player, opponent, root_coord).first()
+        {
+            return attack.action;
+        }
+    }
     let mut possible_coords = HashSet::new();
     fill_all_possible_coord(&mut possible_coords, root_coord, root_coord, grid);
+    // sorted so the same state always gives the same action
+    let mut possible_coords: Vec<Coord> = possible_coords.into_iter().collect();
+    possible_coords.sort_unstable();
 
     let mut action: Action = action::wait();
     for coord in possible_coords {
         if player.can_buy(OrganType::Tentacle) {
-            if let Some(direction) = can_attack_enemy_organ_in_one_cell(grid, player, coord) {
-                return action::growth(OrganType::Tentacle, direction, coord, root_coord);
-            }
             if let Some(direction) = can_attack_enemy_organ_in_two_cell(grid, player, coord) {
                 action = action::growth(OrganType::Tentacle, direction, coord, root_coord);
             }
@@ -33,12 +48,23 @@ pub fn found_an_action(grid: &Grid, player: &Player, root_coord: Coord) -> Actio
                 return action::growth(OrganType::Harvester, direction, coord, root_coord);
             }
         } else if player.can_buy(OrganType::Basic) {
-            return action::growth(OrganType::Root, OrganDirection::North, coord, root_coord);
+            return action::growth(OrganType::Basic, OrganDirection::North, coord, root_coord);
         }
     }
     action
 }
 
+/// `found_an_action` for every root of `owner`, in the referee order.
+pub fn found_actions(state: &GameState, owner: u8) -> Vec<Action> {
+    let player = &state.players[owner as usize];
+    let opponent = &state.players[1 - owner as usize];
+    state
+        .get_roots_in_order(owner)
+        .into_iter()
+        .map(|root| found_an_action(&state.grid, player, opponent, root))
+        .collect()
+}
+
 pub fn fill_all_possible_coord(
     possible_coord: &mut HashSet<Coord>,
     current_coord: Coord,
@@ -59,29 +85,6 @@ pub fn fill_all_possible_coord(
     }
 }
 
-fn can_attack_enemy_organ_in_one_cell(
-    grid: &Grid,
-    player: &Player,
-    coord: Coord,
-) -> Option<OrganDirection> {
-    let organ = organ::new(
-        player.get_id(),
-        OrganType::Basic,
-        OrganDirection::North,
-        coord,
-    );
-    if grid.can_add_organ_without_root_coord(coord, organ)
-        && grid.contains_an_adjacent_organ(
-            coord::x(coord),
-            coord::y(coord),
-            player.get_opponent_id(),
-        )
-    {
-        return grid.get_direction_to_an_adjacent_organ(coord, player.get_opponent_id());
-    }
-    None
-}
-
 fn can_attack_enemy_organ_in_two_cell(
     grid: &Grid,
     player: &Player,
@@ -126,41 +129,30 @@ mod tests {
     fn test_can_attack_enemy_organ_in_one_cell() {
         let mut grid = Grid::new(3, 3);
         let player = Player::new(1);
-        let coord = coord::new(1, 1);
-        let organ_own = organ::new(1, OrganType::Basic, OrganDirection::North, coord);
-        let organ_opp = organ::new(0, OrganType::Basic, OrganDirection::North, coord);
+        let root_coord = coord::new(0, 1);
+        let organ_own = organ::new(1, OrganType::Root, OrganDirection::North, root_coord);
+        let organ_opp = organ::new(0, OrganType::Basic, OrganDirection::North, coord::new(2, 2));
 
         grid.set_cell(0, 1, cell::new(false, None, Some(organ_own)));
 
         grid.set_cell(1, 2, cell::new(false, None, Some(organ_opp)));
-        assert_eq!(
-            can_attack_enemy_organ_in_one_cell(&grid, &player, coord),
-            Some(organ_direction::OrganDirection::South)
-        );
-        assert_eq!(
-            can_attack_enemy_organ_in_one_cell(&grid, &player, coord::new(1, 2)),
-            None
-        );
-        assert_eq!(
-            can_attack_enemy_organ_in_one_cell(&grid, &player, coord::new(1, 0)),
-            None
-        );
-        assert_eq!(
-            can_attack_enemy_organ_in_one_cell(&grid, &player, coord::new(0, 1)),
-            None
-        );
-        assert_eq!(
-            can_attack_enemy_organ_in_one_cell(&grid, &player, coord::new(2, 1)),
-            None
-        );
-        assert_eq!(
-            can_attack_enemy_organ_in_one_cell(&grid, &player, coord::new(0, 0)),
-            None
-        );
-        assert_eq!(
-            can_attack_enemy_organ_in_one_cell(&grid, &player, coord::new(0, 2)),
-            Some(organ_direction::OrganDirection::East)
-        );
+        let mut attacks: Vec<(Coord, OrganDirection)> =
+            attack_planner::find_attacks(&grid, &player, &Player::new(0), root_coord)
+                .into_iter()
+                .map(|attack| {
+                    (
+                        action::get_coord_target(attack.action),
+                        action::get_direction(attack.action),
+                    )
+                })
+                .collect();
+        let mut expected = vec![
+            (coord::new(1, 1), organ_direction::OrganDirection::South),
+            (coord::new(0, 2), organ_direction::OrganDirection::East),
+        ];
+        attacks.sort_by_key(|&(target, _)| target);
+        expected.sort_by_key(|&(target, _)| target);
+        assert_eq!(attacks, expected);
     }
 
     #[test]
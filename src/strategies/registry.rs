@@ -0,0 +1,70 @@
+use crate::simulation::evaluation::EvaluationParams;
+
+use super::{
+    beam_search::{self, BeamSearchConfig, BeamSearchStrategy},
+    genetic_algorithm::{self, GeneticConfig, GeneticStrategy},
+    greedy,
+    mcts::{self, MctsConfig, MctsStrategy},
+    planner,
+    strategy::Strategy,
+};
+
+pub const DEFAULT_STRATEGY: &str = beam_search::NAME;
+
+pub const STRATEGY_NAMES: [&str; 5] = [
+    greedy::NAME,
+    planner::NAME,
+    genetic_algorithm::NAME,
+    mcts::NAME,
+    beam_search::NAME,
+];
+
+/// Strategy called `name` with the default config, but `evaluation` and `seed`.
+pub fn create_strategy(
+    name: &str,
+    evaluation: &EvaluationParams,
+    seed: u64,
+) -> Option<Box<dyn Strategy>> {
+    match name {
+        greedy::NAME => Some(Box::<greedy::GreedyStrategy>::default()),
+        planner::NAME => Some(Box::<planner::PlannerStrategy>::default()),
+        genetic_algorithm::NAME => Some(Box::new(GeneticStrategy::new(GeneticConfig {
+            evaluation: *evaluation,
+            seed,
+            ..GeneticConfig::default()
+        }))),
+        mcts::NAME => Some(Box::new(MctsStrategy::new(MctsConfig {
+            evaluation: *evaluation,
+            seed,
+            ..MctsConfig::default()
+        }))),
+        beam_search::NAME => Some(Box::new(BeamSearchStrategy::new(BeamSearchConfig {
+            evaluation: *evaluation,
+            seed,
+            ..BeamSearchConfig::default()
+        }))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_every_registered_strategy() {
+        for name in STRATEGY_NAMES {
+            assert_eq!(
+                create_strategy(name, &EvaluationParams::default(), 0)
+                    .unwrap()
+                    .get_name(),
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_create_unknown_strategy() {
+        assert!(create_strategy("unknown", &EvaluationParams::default(), 0).is_none());
+    }
+}
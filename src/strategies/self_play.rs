@@ -0,0 +1,321 @@
+use std::io::{self, Write};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    actions::{action::Action, action_command, actions_coordinator},
+    game_entities::{
+        game_state::{wallet_from_counts, Entity, GameState, MY_ID},
+        protein::PROTEINS,
+    },
+    simulation::{
+        evaluation::{self, EvaluationParams},
+        turn_simulator,
+        value_network::get_inputs,
+    },
+};
+
+use super::{
+    bot_memory::BotMemory, opponent_model::OpponentModel, registry, strategy::Strategy,
+    time_manager::TimeManager,
+};
+
+/// Proteins of each type both players start with.
+pub const START_PROTEINS: u32 = 10;
+
+pub const WALL_PROBABILITY: f64 = 0.15;
+
+pub const SOURCE_PROBABILITY: f64 = 0.05;
+
+/// Games to play and who plays them, the strategy of player `i` being `strategies[i]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfPlayConfig {
+    pub games: usize,
+    pub strategies: [String; 2],
+    pub evaluation: EvaluationParams,
+    pub seed: u64,
+    /// `TimeManager::should_stop` calls per turn, in place of the clock.
+    pub checks_per_turn: u32,
+}
+
+/// Point symmetric map with random walls and sources, one root per player.
+pub fn generate_map(rng: &mut StdRng) -> GameState {
+    let width: u8 = rng.gen_range(16..=24);
+    let height: u8 = rng.gen_range(8..=12);
+    let root = (1, rng.gen_range(1..height / 2));
+    let mirror = |(x, y): (u8, u8)| (width - 1 - x, height - 1 - y);
+    let is_near_a_root = |(x, y): (u8, u8)| {
+        [root, mirror(root)]
+            .iter()
+            .any(|&(root_x, root_y)| x.abs_diff(root_x) + y.abs_diff(root_y) <= 1)
+    };
+    let mut entities = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            // the first half of the cells decides for the other half
+            if (y as usize * width as usize + x as usize) * 2 >= width as usize * height as usize
+                || is_near_a_root((x, y))
+            {
+                continue;
+            }
+            let entity_type = if rng.gen_bool(WALL_PROBABILITY) {
+                "WALL"
+            } else if rng.gen_bool(SOURCE_PROBABILITY) {
+                PROTEINS[rng.gen_range(0..PROTEINS.len())].to_str()
+            } else {
+                continue;
+            };
+            for (x, y) in [(x, y), mirror((x, y))] {
+                entities.push(Entity::from_line(&format!(
+                    "{} {} {} -1 0 X 0 0",
+                    x, y, entity_type
+                )));
+            }
+        }
+    }
+    let (x, y) = mirror(root);
+    entities.push(Entity::from_line(&format!(
+        "{} {} ROOT 0 1 N 0 1",
+        root.0, root.1
+    )));
+    entities.push(Entity::from_line(&format!("{} {} ROOT 1 2 N 0 2", x, y)));
+    let wallet = wallet_from_counts(
+        START_PROTEINS,
+        START_PROTEINS,
+        START_PROTEINS,
+        START_PROTEINS,
+    );
+    GameState::from_entities(width, height, &entities, [wallet, wallet], 1)
+}
+
+/// Plays `config.games` games, writing one JSON line per turn then one per game outcome,
+/// as soon as they are known. The same seed gives the same file.
+pub fn run(config: &SelfPlayConfig, output: &mut impl Write) -> io::Result<()> {
+    for game in 0..config.games {
+        let seed = config.seed.wrapping_add(game as u64);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let state = generate_map(&mut rng);
+        play_game(config, game, seed, state, output)?;
+    }
+    output.flush()
+}
+
+fn play_game(
+    config: &SelfPlayConfig,
+    game: usize,
+    seed: u64,
+    mut state: GameState,
+    output: &mut impl Write,
+) -> io::Result<()> {
+    let mut strategies = config.strategies.clone().map(|name| {
+        registry::create_strategy(&name, &config.evaluation, seed)
+            .unwrap_or_else(|| panic!("Unknown strategy {}", name))
+    });
+    let mut memories = [BotMemory::new(), BotMemory::new()];
+    let mut time = TimeManager::with_max_checks(config.checks_per_turn);
+    let raw_weights = EvaluationParams {
+        organs: 1.0,
+        income: 1.0,
+        wallet: 1.0,
+        territory: 1.0,
+        threatened: 1.0,
+        sources_reached_first: 1.0,
+        roots: 1.0,
+        value_network: 0.0,
+    };
+    while !turn_simulator::is_game_over(&state) {
+        let actions = play_turn(&mut strategies, &mut memories, &state, &mut time);
+        let features = evaluation::evaluate(&state, 0, &raw_weights);
+        writeln!(
+            output,
+            "{{\"game\":{},\"turn\":{},\"inputs\":[{},{}],\"features\":{},\"actions\":[{},{}]}}",
+            game,
+            state.turn,
+            to_json_numbers(&get_inputs(&state, 0)),
+            to_json_numbers(&get_inputs(&state, 1)),
+            to_json_numbers(&features.get_features().map(|(_, value)| value)),
+            to_json_commands(&state, &actions[0]),
+            to_json_commands(&state, &actions[1]),
+        )?;
+        turn_simulator::simulate_turn(&mut state, &actions);
+    }
+    let scores = [0, 1].map(|owner| state.players[owner].get_score());
+    let winner = match scores[0].cmp(&scores[1]) {
+        std::cmp::Ordering::Greater => 0,
+        std::cmp::Ordering::Less => 1,
+        std::cmp::Ordering::Equal => -1,
+    };
+    writeln!(
+        output,
+        "{{\"game\":{},\"seed\":{},\"turns\":{},\"scores\":[{},{}],\"winner\":{}}}",
+        game, seed, state.turn, scores[0], scores[1], winner
+    )
+}
+
+/// Actions of both players, each playing `MY_ID` in its own view with the whole turn budget.
+/// Each player remembers its view like the bot does between turns.
+fn play_turn(
+    strategies: &mut [Box<dyn Strategy>; 2],
+    memories: &mut [BotMemory; 2],
+    state: &GameState,
+    time: &mut TimeManager,
+) -> [Vec<Action>; 2] {
+    let mut actions: [Vec<Action>; 2] = [Vec::new(), Vec::new()];
+    for (owner, (strategy, memory)) in strategies.iter_mut().zip(memories.iter_mut()).enumerate() {
+        let owner = owner as u8;
+        time.start_turn(state.turn == 0);
+        // strategies play MY_ID, so the other player gets the swapped position
+        let view = if owner == MY_ID {
+            state.clone()
+        } else {
+            state.with_swapped_players()
+        };
+        memory.record_turn(&view);
+        strategy.observe_opponent(&OpponentModel::from_history(memory.get_history()));
+        if let Some(inferred) = memory.get_last_inference() {
+            strategy.observe_last_turn(inferred, memory.get_last_actions());
+        }
+        let chosen =
+            actions_coordinator::make_consistent(&view, MY_ID, &strategy.play(&view, time));
+        actions[owner as usize] = actions_coordinator::secure(state, owner, &chosen).0;
+        memory.record_actions(&actions[owner as usize]);
+    }
+    actions
+}
+
+fn to_json_numbers(values: &[f64]) -> String {
+    let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+    format!("[{}]", values.join(","))
+}
+
+fn to_json_commands(state: &GameState, actions: &[Action]) -> String {
+    let commands: Vec<String> = actions
+        .iter()
+        .map(|&action| format!("\"{}\"", action_command::to_command(action, state)))
+        .collect();
+    format!("[{}]", commands.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actions::action,
+        game_entities::{cell, coord},
+        strategies::{genetic_algorithm, greedy, mcts},
+    };
+
+    use super::*;
+
+    fn config(strategy: &str) -> SelfPlayConfig {
+        SelfPlayConfig {
+            games: 2,
+            strategies: [strategy.to_string(), strategy.to_string()],
+            evaluation: EvaluationParams::default(),
+            seed: 3,
+            checks_per_turn: 1,
+        }
+    }
+
+    #[test]
+    fn test_generated_map_is_symmetric() {
+        let state = generate_map(&mut StdRng::seed_from_u64(0));
+        let grid = &state.grid;
+        let (width, height) = (grid.width, grid.height);
+
+        for coord in grid.iter_coords() {
+            let mirrored = coord::new(width - 1 - coord::x(coord), height - 1 - coord::y(coord));
+            let (cell, other) = (
+                grid.get_cell_from_coord(coord),
+                grid.get_cell_from_coord(mirrored),
+            );
+            assert_eq!(cell::is_obstacle(cell), cell::is_obstacle(other));
+            assert_eq!(cell::get_protein(cell), cell::get_protein(other));
+        }
+        assert_eq!(state.get_roots_in_order(0).len(), 1);
+        assert_eq!(state.get_roots_in_order(1).len(), 1);
+        let root = state.get_roots_in_order(0)[0];
+        assert!(grid
+            .get_adjacent_coords(root)
+            .iter()
+            .all(|&adjacent| cell::is_growable(grid.get_cell_from_coord(adjacent))));
+    }
+
+    #[test]
+    fn test_run_is_reproducible() {
+        let mut first = Vec::new();
+        let mut second = Vec::new();
+        run(&config(greedy::NAME), &mut first).unwrap();
+        run(&config(greedy::NAME), &mut second).unwrap();
+
+        assert_eq!(first, second);
+        let text = String::from_utf8(first).unwrap();
+        let outcomes: Vec<&str> = text
+            .lines()
+            .filter(|line| line.contains("\"winner\""))
+            .collect();
+        assert_eq!(outcomes.len(), 2);
+        assert!(text.lines().count() > 2);
+        assert!(text
+            .lines()
+            .all(|line| line.starts_with("{\"game\":") && line.ends_with('}')));
+    }
+
+    #[test]
+    fn test_run_is_reproducible_with_random_strategies() {
+        for name in [genetic_algorithm::NAME, mcts::NAME] {
+            let config = SelfPlayConfig {
+                games: 1,
+                checks_per_turn: 3,
+                ..config(name)
+            };
+            let mut first = Vec::new();
+            let mut second = Vec::new();
+            run(&config, &mut first).unwrap();
+            run(&config, &mut second).unwrap();
+
+            assert_eq!(first, second, "{} is not reproducible", name);
+        }
+    }
+
+    /// Waits after counting the `should_stop` calls it got before the time ran out.
+    struct CountingStrategy {
+        checks: Rc<RefCell<Vec<u32>>>,
+    }
+
+    impl Strategy for CountingStrategy {
+        fn get_name(&self) -> &'static str {
+            "counting"
+        }
+
+        fn play(&mut self, _state: &GameState, time: &TimeManager) -> Vec<Action> {
+            let mut checks = 1;
+            while !time.should_stop() {
+                checks += 1;
+            }
+            self.checks.borrow_mut().push(checks);
+            vec![action::wait()]
+        }
+    }
+
+    #[test]
+    fn test_mirror_match_gives_both_players_the_same_budget() {
+        let state = generate_map(&mut StdRng::seed_from_u64(0));
+        let checks = Rc::new(RefCell::new(Vec::new()));
+        let mut strategies: [Box<dyn Strategy>; 2] = [
+            Box::new(CountingStrategy {
+                checks: checks.clone(),
+            }),
+            Box::new(CountingStrategy {
+                checks: checks.clone(),
+            }),
+        ];
+        let mut time = TimeManager::with_max_checks(5);
+        let mut memories = [BotMemory::new(), BotMemory::new()];
+        let actions = play_turn(&mut strategies, &mut memories, &state, &mut time);
+
+        assert_eq!(*checks.borrow(), vec![5, 5]);
+        assert_eq!(actions, [vec![action::wait()], vec![action::wait()]]);
+    }
+}
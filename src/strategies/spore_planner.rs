@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use crate::{
+    actions::{
+        action::{self, Action},
+        actions_finder::{self, DIRECTIONS},
+    },
+    entites_managers::proteins_manager::{ProteinsManager, UNREACHABLE},
+    game_entities::{
+        cell, coord::Coord, grid::Grid, organ, organ_direction::OrganDirection,
+        organ_type::OrganType, player::Player, protein_wallet::ProteinWallet,
+    },
+};
+
+/// Score of a cell reached strictly before the opponent.
+pub const TERRITORY_WEIGHT: f64 = 1.0;
+
+/// Score of a free protein source reached strictly before the opponent.
+pub const SOURCE_WEIGHT: f64 = 3.0;
+
+/// Grow a sporer, then shoot a spore from it next turn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SporePlan {
+    pub sporer: Action,
+    pub spore: Action,
+    /// SPORER and ROOT costs together.
+    pub cost: ProteinWallet,
+    pub score: f64,
+}
+
+/// Scores the spore landings of a player by the territory and sources they capture.
+pub struct SporePlanner {
+    owner: u8,
+    territory: usize,
+    sources: usize,
+    landing_scores: HashMap<Coord, f64>,
+}
+
+impl SporePlanner {
+    pub fn new(grid: &Grid, owner: u8) -> Self {
+        let (territory, sources) = count_captures(grid, owner);
+        SporePlanner {
+            owner,
+            territory,
+            sources,
+            landing_scores: HashMap::new(),
+        }
+    }
+
+    /// Territory and sources gained by a new root on `landing`.
+    pub fn get_landing_score(&mut self, grid: &Grid, landing: Coord) -> f64 {
+        if let Some(&score) = self.landing_scores.get(&landing) {
+            return score;
+        }
+        let mut landed = grid.clone();
+        landed.add_organ(
+            landing,
+            organ::new(self.owner, OrganType::Root, OrganDirection::North, landing),
+        );
+        let (territory, sources) = count_captures(&landed, self.owner);
+        let score = TERRITORY_WEIGHT * (territory as f64 - self.territory as f64)
+            + SOURCE_WEIGHT * (sources as f64 - self.sources as f64);
+        self.landing_scores.insert(landing, score);
+        score
+    }
+
+    /// Every sporer placement and direction of the organism rooted at `root_coord`,
+    /// with its best landing, best plan first.
+    pub fn find_plans(&mut self, grid: &Grid, root_coord: Coord) -> Vec<SporePlan> {
+        let cost = OrganType::Sporer.get_cost() + OrganType::Root.get_cost();
+        let new_root = organ::new(self.owner, OrganType::Root, OrganDirection::North, 0);
+        let mut plans = Vec::new();
+        let frontier: Vec<Coord> = grid.iter_root_frontier(self.owner, root_coord).collect();
+        for sporer_coord in frontier {
+            for direction in DIRECTIONS {
+                let mut best: Option<(Coord, f64)> = None;
+                let mut current = grid.get_next_coord(sporer_coord, direction);
+                while let Some(landing) = current {
+                    if !cell::is_growable(grid.get_cell_from_coord(landing)) {
+                        break;
+                    }
+                    if grid.can_add_organ_without_root_coord(landing, new_root) {
+                        let score = self.get_landing_score(grid, landing);
+                        let is_better = match best {
+                            Some((_, best_score)) => score > best_score,
+                            None => true,
+                        };
+                        if is_better {
+                            best = Some((landing, score));
+                        }
+                    }
+                    current = grid.get_next_coord(landing, direction);
+                }
+                if let Some((landing, score)) = best {
+                    plans.push(SporePlan {
+                        sporer: action::growth(
+                            OrganType::Sporer,
+                            direction,
+                            sporer_coord,
+                            root_coord,
+                        ),
+                        spore: action::sporer(direction, landing, root_coord),
+                        cost,
+                        score,
+                    });
+                }
+            }
+        }
+        plans.sort_by(|a, b| b.score.total_cmp(&a.score));
+        plans
+    }
+
+    /// Best SPORE of the sporers already grown, the second step of a plan.
+    pub fn find_best_spore(
+        &mut self,
+        grid: &Grid,
+        player: &Player,
+        root_coord: Coord,
+    ) -> Option<(Action, f64)> {
+        actions_finder::find_spore_actions(grid, player, root_coord)
+            .into_iter()
+            .map(|spore| {
+                let score = self.get_landing_score(grid, action::get_coord_target(spore));
+                (spore, score)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+/// Cells and free sources `owner` reaches strictly before its opponent.
+fn count_captures(grid: &Grid, owner: u8) -> (usize, usize) {
+    let proteins_manager = ProteinsManager::new(grid);
+    let territory = grid
+        .iter_coords()
+        .filter(|&coord| cell::is_growable(grid.get_cell_from_coord(coord)))
+        .filter(|&coord| {
+            let distance = proteins_manager.get_distance(grid, owner, coord);
+            distance != UNREACHABLE
+                && distance < proteins_manager.get_distance(grid, 1 - owner, coord)
+        })
+        .count();
+    (
+        territory,
+        proteins_manager.get_contested_sources_won_by(owner).len(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        actions::action_type::ActionType,
+        game_entities::{
+            coord,
+            game_state::{wallet_from_counts, Entity, GameState, MY_ID},
+            protein::Protein,
+            protein_wallet,
+        },
+    };
+
+    use super::*;
+
+    fn build_state() -> GameState {
+        let entities: Vec<Entity> = [
+            "0 1 ROOT 1 1 N 0 1",
+            "11 0 ROOT 0 2 N 0 2",
+            "7 1 A -1 0 X 0 0",
+        ]
+        .iter()
+        .map(|line| Entity::from_line(line))
+        .collect();
+        GameState::from_entities(
+            12,
+            3,
+            &entities,
+            [
+                wallet_from_counts(0, 0, 0, 0),
+                wallet_from_counts(2, 2, 2, 2),
+            ],
+            1,
+        )
+    }
+
+    #[test]
+    fn test_landing_score_counts_captures() {
+        let state = build_state();
+        let mut planner = SporePlanner::new(&state.grid, MY_ID);
+
+        // next to the source, the new root wins it and the cells around it
+        let far = planner.get_landing_score(&state.grid, coord::new(6, 1));
+        let near = planner.get_landing_score(&state.grid, coord::new(2, 1));
+        assert!(far >= SOURCE_WEIGHT);
+        assert!(far > near);
+        assert!(near >= 0.0);
+    }
+
+    #[test]
+    fn test_find_plans() {
+        let state = build_state();
+        let mut planner = SporePlanner::new(&state.grid, MY_ID);
+        let plans = planner.find_plans(&state.grid, coord::new(0, 1));
+
+        assert!(!plans.is_empty());
+        assert!(plans.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        let best = plans[0];
+        assert_eq!(action::get_organ_type(best.sporer), OrganType::Sporer);
+        assert!(action::is_valid_with_root(
+            best.sporer,
+            &state.grid,
+            state.get_me()
+        ));
+        assert_eq!(action::get_type(best.spore), ActionType::Sporer);
+        assert_eq!(
+            action::get_direction(best.spore),
+            action::get_direction(best.sporer)
+        );
+        assert!(coord::x(action::get_coord_target(best.spore)) > 2);
+        assert_eq!(protein_wallet::get(best.cost, Protein::A), 1);
+        assert_eq!(protein_wallet::get(best.cost, Protein::D), 2);
+    }
+
+    #[test]
+    fn test_find_best_spore_after_the_sporer_grew() {
+        let mut state = build_state();
+        let mut planner = SporePlanner::new(&state.grid, MY_ID);
+        assert_eq!(
+            planner.find_best_spore(&state.grid, state.get_me(), coord::new(0, 1)),
+            None
+        );
+
+        let plan = planner.find_plans(&state.grid, coord::new(0, 1))[0];
+        let sporer_coord = action::get_coord_target(plan.sporer);
+        state.add_organ(
+            sporer_coord,
+            organ::new(
+                MY_ID,
+                OrganType::Sporer,
+                action::get_direction(plan.sporer),
+                coord::new(0, 1),
+            ),
+        );
+        let (spore, score) = planner
+            .find_best_spore(&state.grid, state.get_me(), coord::new(0, 1))
+            .unwrap();
+        assert_eq!(
+            action::get_direction(spore),
+            action::get_direction(plan.spore)
+        );
+        assert_eq!(score, plan.score);
+    }
+}
@@ -0,0 +1,18 @@
+use crate::{actions::action::Action, game_entities::game_state::GameState};
+
+use super::{bot_memory::InferredTurn, opponent_model::OpponentModel, time_manager::TimeManager};
+
+pub trait Strategy {
+    fn get_name(&self) -> &'static str;
+
+    /// One action per root of ours, in `GameState::get_roots_in_order` order,
+    /// anytime strategies return before `time` runs out.
+    fn play(&mut self, state: &GameState, time: &TimeManager) -> Vec<Action>;
+
+    /// Called each turn before `play` with what is known of the opponent.
+    fn observe_opponent(&mut self, _model: &OpponentModel) {}
+
+    /// Called each turn but the first before `play` with what the opponent did since the
+    /// previous turn and the actions we printed then.
+    fn observe_last_turn(&mut self, _inferred: &InferredTurn, _our_actions: &[Action]) {}
+}
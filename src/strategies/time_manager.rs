@@ -0,0 +1,171 @@
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+pub const FIRST_TURN_BUDGET: Duration = Duration::from_millis(1000);
+
+pub const TURN_BUDGET: Duration = Duration::from_millis(50);
+
+/// Kept for the parsing, the command formatting and the OS scheduling.
+pub const SAFETY_MARGIN: Duration = Duration::from_millis(8);
+
+/// The last measure counts for 1 / FLUSH_SMOOTHING of the flush latency average.
+const FLUSH_SMOOTHING: u32 = 4;
+
+/// Time left in the current turn, shared by every anytime search.
+#[derive(Debug, Clone)]
+pub struct TimeManager {
+    start: Instant,
+    budget: Duration,
+    /// Budget of every turn but the first.
+    turn_budget: Duration,
+    safety_margin: Duration,
+    flush_latency: Duration,
+    /// When set, `should_stop` answers true after that many calls instead of watching
+    /// the clock, so offline runs are reproducible.
+    max_checks: Option<u32>,
+    checks: Cell<u32>,
+}
+
+impl TimeManager {
+    pub fn new() -> Self {
+        TimeManager::with_budget(FIRST_TURN_BUDGET, SAFETY_MARGIN)
+    }
+
+    /// Starts a turn right away, used by the tests and the offline tools.
+    pub fn with_budget(budget: Duration, safety_margin: Duration) -> Self {
+        TimeManager {
+            start: Instant::now(),
+            budget,
+            turn_budget: TURN_BUDGET,
+            safety_margin,
+            flush_latency: Duration::ZERO,
+            max_checks: None,
+            checks: Cell::new(0),
+        }
+    }
+
+    /// Stops every search after `max_checks` calls to `should_stop` per turn.
+    pub fn with_max_checks(max_checks: u32) -> Self {
+        TimeManager {
+            max_checks: Some(max_checks),
+            ..TimeManager::new()
+        }
+    }
+
+    /// To call as soon as the first input line of the turn arrives.
+    pub fn start_turn(&mut self, is_first_turn: bool) {
+        self.start = Instant::now();
+        self.checks.set(0);
+        self.budget = if is_first_turn {
+            FIRST_TURN_BUDGET
+        } else {
+            self.turn_budget
+        };
+    }
+
+    pub fn set_turn_budget(&mut self, turn_budget: Duration) {
+        self.turn_budget = turn_budget;
+    }
+
+    /// Measured time to write the commands, removed from the next budgets.
+    pub fn record_flush(&mut self, latency: Duration) {
+        self.flush_latency =
+            (self.flush_latency * (FLUSH_SMOOTHING - 1) + latency) / FLUSH_SMOOTHING;
+    }
+
+    pub fn get_flush_latency(&self) -> Duration {
+        self.flush_latency
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.budget
+            .saturating_sub(self.safety_margin)
+            .saturating_sub(self.flush_latency)
+            .saturating_sub(self.elapsed())
+    }
+
+    pub fn should_stop(&self) -> bool {
+        match self.max_checks {
+            Some(max_checks) => {
+                self.checks.set(self.checks.get() + 1);
+                self.checks.get() >= max_checks
+            }
+            None => self.remaining() == Duration::ZERO,
+        }
+    }
+}
+
+impl Default for TimeManager {
+    fn default() -> Self {
+        TimeManager::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_turn_budgets() {
+        let mut time = TimeManager::new();
+
+        time.start_turn(true);
+        assert!(time.remaining() > TURN_BUDGET);
+        time.start_turn(false);
+        assert!(time.remaining() <= TURN_BUDGET - SAFETY_MARGIN);
+        assert!(!time.should_stop());
+    }
+
+    #[test]
+    fn test_configured_turn_budget() {
+        let mut time = TimeManager::new();
+        time.set_turn_budget(Duration::from_millis(200));
+
+        time.start_turn(false);
+        assert!(time.remaining() > TURN_BUDGET);
+        assert!(time.remaining() <= Duration::from_millis(200) - SAFETY_MARGIN);
+    }
+
+    #[test]
+    fn test_max_checks_ignore_the_clock() {
+        let mut time = TimeManager::with_max_checks(2);
+
+        assert!(!time.should_stop());
+        assert!(time.should_stop());
+        time.start_turn(false);
+        assert!(!time.should_stop());
+    }
+
+    #[test]
+    fn test_should_stop_once_spent() {
+        let time = TimeManager::with_budget(Duration::from_millis(2), Duration::ZERO);
+
+        std::thread::sleep(Duration::from_millis(3));
+        assert_eq!(time.remaining(), Duration::ZERO);
+        assert!(time.should_stop());
+    }
+
+    #[test]
+    fn test_margin_larger_than_budget() {
+        let time = TimeManager::with_budget(Duration::from_millis(5), Duration::from_millis(10));
+
+        assert!(time.should_stop());
+    }
+
+    #[test]
+    fn test_flush_latency_reduces_budget() {
+        let mut time = TimeManager::with_budget(Duration::from_millis(50), Duration::ZERO);
+
+        time.record_flush(Duration::from_millis(8));
+        assert_eq!(time.get_flush_latency(), Duration::from_millis(2));
+        time.record_flush(Duration::from_millis(2));
+        assert_eq!(time.get_flush_latency(), Duration::from_millis(2));
+        assert!(time.remaining() <= Duration::from_millis(48));
+    }
+}